@@ -0,0 +1,25 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use tip5_hash::BFieldElement;
+
+fn inverse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inverse");
+
+    for size in [1_000, 100_000] {
+        let elements = (1..=size).map(BFieldElement::new).collect::<Vec<_>>();
+
+        group.bench_with_input(
+            BenchmarkId::new("element-wise", size),
+            &elements,
+            |b, elements| b.iter(|| elements.iter().map(|e| e.inverse()).collect::<Vec<_>>()),
+        );
+        group.bench_with_input(BenchmarkId::new("batch", size), &elements, |b, elements| {
+            b.iter(|| BFieldElement::batch_inverse(black_box(elements)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, inverse);
+criterion_main!(benches);
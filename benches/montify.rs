@@ -0,0 +1,51 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use tip5_hash::BFieldElement;
+
+fn montify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("montify");
+
+    let size = 1_000_000;
+    let values = (0..size)
+        .map(|i| 0x1234_5678_9abc_def0_u64.wrapping_mul(i))
+        .collect::<Vec<_>>();
+    let elements = BFieldElement::montify_slice(&values);
+
+    group.bench_function(BenchmarkId::new("element-wise new", size), |b| {
+        b.iter(|| {
+            let mut elements = Vec::new();
+            for &value in black_box(&values) {
+                elements.push(BFieldElement::new(value));
+            }
+            elements
+        })
+    });
+    group.bench_function(BenchmarkId::new("montify_slice", size), |b| {
+        b.iter(|| BFieldElement::montify_slice(black_box(&values)))
+    });
+    group.bench_function(BenchmarkId::new("montify_in_place", size), |b| {
+        let mut buffer = values.clone();
+        b.iter(|| {
+            buffer.copy_from_slice(black_box(&values));
+            BFieldElement::montify_in_place(&mut buffer).len()
+        })
+    });
+
+    group.bench_function(BenchmarkId::new("element-wise value", size), |b| {
+        b.iter(|| {
+            let mut values = Vec::new();
+            for element in black_box(&elements) {
+                values.push(element.value());
+            }
+            values
+        })
+    });
+    group.bench_function(BenchmarkId::new("canonical_values", size), |b| {
+        b.iter(|| BFieldElement::canonical_values(black_box(&elements)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, montify);
+criterion_main!(benches);
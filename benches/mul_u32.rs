@@ -0,0 +1,36 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use tip5_hash::{BFieldElement, MDS_MATRIX_FIRST_COLUMN};
+
+fn mul_u32(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mul_u32");
+
+    let elements = (0..1_000)
+        .map(|i| BFieldElement::new(0x1234_5678_9abc_def0 ^ i))
+        .collect::<Vec<_>>();
+    let scalars = MDS_MATRIX_FIRST_COLUMN.map(|k| k as u32);
+
+    group.bench_function("general multiplication", |b| {
+        b.iter(|| {
+            black_box(&elements)
+                .iter()
+                .zip(scalars.iter().cycle())
+                .map(|(&x, &k)| x * BFieldElement::new(k as u64))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("mul_u32", |b| {
+        b.iter(|| {
+            black_box(&elements)
+                .iter()
+                .zip(scalars.iter().cycle())
+                .map(|(&x, &k)| x.mul_u32(k))
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, mul_u32);
+criterion_main!(benches);
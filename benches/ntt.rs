@@ -0,0 +1,27 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use tip5_hash::{BFieldElement, intt, ntt};
+
+fn ntt_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ntt");
+
+    for log_2_of_size in [16, 20] {
+        let size = 1_u64 << log_2_of_size;
+        let elements = (0..size)
+            .map(|i| BFieldElement::new(0x1234_5678_9abc_def0 ^ i))
+            .collect::<Vec<_>>();
+
+        group.bench_with_input(BenchmarkId::new("ntt", size), &elements, |b, elements| {
+            let mut buffer = elements.clone();
+            b.iter(|| ntt(&mut buffer))
+        });
+        group.bench_with_input(BenchmarkId::new("intt", size), &elements, |b, elements| {
+            let mut buffer = elements.clone();
+            b.iter(|| intt(&mut buffer))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, ntt_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::iter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use itertools::Itertools;
+use num_traits::{ConstOne, ConstZero};
+use tip5_hash::{BFieldElement, RATE, Sponge, Tip5};
+
+/// Counts allocations, to check that absorbing does not allocate.
+struct CountingAllocator;
+
+static NUM_ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: all calls are forwarded to the system allocator
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        NUM_ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The previous implementation, which collected every chunk into a `Vec`.
+fn collecting_pad_and_absorb_all(sponge: &mut Tip5, input: &[BFieldElement]) {
+    let padded_length = (input.len() + 1).next_multiple_of(RATE);
+    let padding_iter = iter::once(&BFieldElement::ONE).chain(iter::repeat(&BFieldElement::ZERO));
+    let padded_input = input.iter().chain(padding_iter).take(padded_length);
+
+    for chunk in padded_input.chunks(RATE).into_iter() {
+        let absorb_elems: [_; RATE] = chunk.cloned().collect_vec().try_into().unwrap();
+        sponge.absorb(&absorb_elems);
+    }
+}
+
+fn num_allocations(f: impl FnOnce()) -> usize {
+    let before = NUM_ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    NUM_ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn pad_and_absorb(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pad_and_absorb_all");
+
+    let size = 1 << 16;
+    let input = (0..size).map(BFieldElement::new).collect_vec();
+
+    let current = num_allocations(|| Tip5::init().pad_and_absorb_all(black_box(&input)));
+    assert_eq!(0, current, "absorbing {size} elements must not allocate");
+
+    group.bench_function(BenchmarkId::new("collecting", size), |b| {
+        b.iter(|| collecting_pad_and_absorb_all(&mut Tip5::init(), black_box(&input)))
+    });
+    group.bench_function(BenchmarkId::new("current", size), |b| {
+        b.iter(|| Tip5::init().pad_and_absorb_all(black_box(&input)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, pad_and_absorb);
+criterion_main!(benches);
@@ -0,0 +1,24 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use num_traits::ConstZero;
+use std::hint::black_box;
+use tip5_hash::{BFieldElement, Domain, STATE_SIZE, Tip5};
+
+fn permutation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("permutation");
+
+    let input = [BFieldElement::ZERO; 10];
+    group.bench_function("hash_10", |b| b.iter(|| Tip5::hash_10(black_box(&input))));
+
+    let mut sponge = Tip5::new(Domain::FixedLength);
+    group.bench_function("permute", |b| b.iter(|| black_box(&mut sponge).permute()));
+
+    let mut state = [BFieldElement::ZERO; STATE_SIZE];
+    group.bench_function("permutation", |b| {
+        b.iter(|| tip5_hash::permutation(black_box(&mut state)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, permutation);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tip5_hash::BFieldElement;
+
+fn random(c: &mut Criterion) {
+    let mut group = c.benchmark_group("random");
+
+    let size = 1_000_000;
+    let mut rng = StdRng::seed_from_u64(0);
+
+    group.bench_function(BenchmarkId::new("element-wise", size), |b| {
+        b.iter(|| {
+            (0..size)
+                .map(|_| rng.r#gen())
+                .collect::<Vec<BFieldElement>>()
+        })
+    });
+    group.bench_function(BenchmarkId::new("bulk", size), |b| {
+        b.iter(|| BFieldElement::random_elements(&mut rng, size))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, random);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use num_traits::ConstZero;
+use std::hint::black_box;
+use tip5_hash::{BFieldElement, Sponge, Tip5};
+
+fn squeeze(c: &mut Criterion) {
+    let mut group = c.benchmark_group("squeeze");
+
+    let size = 1 << 16;
+    let mut sponge = Tip5::init();
+    sponge.pad_and_absorb_all(&[BFieldElement::ZERO; 3]);
+
+    group.bench_function(BenchmarkId::new("squeeze_n", size), |b| {
+        b.iter(|| black_box(&mut sponge).squeeze_n(size))
+    });
+    group.bench_function(BenchmarkId::new("squeeze_into", size), |b| {
+        let mut output = vec![BFieldElement::ZERO; size];
+        b.iter(|| black_box(&mut sponge).squeeze_into(&mut output))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, squeeze);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+//! Conversions between [`BFieldElement`] and the equivalent [arkworks](ark_ff) field
+//! [`ArkGoldilocks`].
+//!
+//! All conversions go through the canonical values, so the internal Montgomery
+//! representations of the two crates, which use different constants, never mix.
+
+use ark_ff::{BigInt, Fp64, MontBackend, MontConfig, PrimeField};
+
+use crate::b_field_element::BFieldElement;
+
+/// The arkworks configuration of the field with [`BFieldElement::P`] elements.
+#[derive(MontConfig)]
+#[modulus = "18446744069414584321"]
+#[generator = "7"]
+pub struct GoldilocksConfig;
+
+/// The arkworks type of the field with [`BFieldElement::P`] elements.
+pub type ArkGoldilocks = Fp64<MontBackend<GoldilocksConfig, 1>>;
+
+impl From<BFieldElement> for ArkGoldilocks {
+    #[inline]
+    fn from(element: BFieldElement) -> Self {
+        // canonical, so `new` performs no reduction
+        Self::new(BigInt([element.value()]))
+    }
+}
+
+impl From<ArkGoldilocks> for BFieldElement {
+    #[inline]
+    fn from(element: ArkGoldilocks) -> Self {
+        let BigInt([value]) = element.into_bigint();
+        Self::new(value)
+    }
+}
+
+/// Convert the given elements into their arkworks equivalents.
+pub fn to_ark(elements: &[BFieldElement]) -> Vec<ArkGoldilocks> {
+    elements.iter().map(|&element| element.into()).collect()
+}
+
+/// Convert the given arkworks elements into [`BFieldElement`]s.
+pub fn from_ark(elements: &[ArkGoldilocks]) -> Vec<BFieldElement> {
+    elements.iter().map(|&element| element.into()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::{FftField, Field, One, Zero};
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn field_parameters_agree() {
+        let BigInt([modulus]) = ArkGoldilocks::MODULUS;
+        assert_eq!(BFieldElement::P, modulus);
+        assert_eq!(BFieldElement::TWO_ADICITY, ArkGoldilocks::TWO_ADICITY);
+
+        let root_of_unity = BFieldElement::from(ArkGoldilocks::TWO_ADIC_ROOT_OF_UNITY);
+        assert_eq!(BFieldElement::TWO_ADIC_ROOT_OF_UNITY, root_of_unity);
+
+        let generator = BFieldElement::from(GoldilocksConfig::GENERATOR);
+        assert_eq!(BFieldElement::GENERATOR, generator);
+    }
+
+    #[test]
+    fn special_elements_convert() {
+        let zero = BFieldElement::from(ArkGoldilocks::zero());
+        let one = BFieldElement::from(ArkGoldilocks::one());
+        assert_eq!(BFieldElement::new(0), zero);
+        assert_eq!(BFieldElement::new(1), one);
+
+        let minus_one = BFieldElement::new(BFieldElement::P - 1);
+        assert_eq!(-ArkGoldilocks::one(), ArkGoldilocks::from(minus_one));
+    }
+
+    #[test]
+    fn slice_conversion_of_empty_slices() {
+        assert!(to_ark(&[]).is_empty());
+        assert!(from_ark(&[]).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn conversion_round_trip(value: u64) {
+            let element = BFieldElement::new(value);
+            let ark = ArkGoldilocks::from(element);
+            prop_assert_eq!(element, BFieldElement::from(ark));
+            prop_assert_eq!(ArkGoldilocks::from(value), ark);
+        }
+
+        #[test]
+        fn slice_conversion_round_trip(values in proptest::collection::vec(any::<u64>(), 0..100)) {
+            let elements = values.into_iter().map(BFieldElement::new).collect::<Vec<_>>();
+            let ark = to_ark(&elements);
+            prop_assert_eq!(elements.len(), ark.len());
+            prop_assert_eq!(elements, from_ark(&ark));
+        }
+
+        #[test]
+        fn arithmetic_agrees(a: u64, b: u64) {
+            let (a, b) = (BFieldElement::new(a), BFieldElement::new(b));
+            let (ark_a, ark_b) = (ArkGoldilocks::from(a), ArkGoldilocks::from(b));
+
+            prop_assert_eq!(a + b, BFieldElement::from(ark_a + ark_b));
+            prop_assert_eq!(a - b, BFieldElement::from(ark_a - ark_b));
+            prop_assert_eq!(a * b, BFieldElement::from(ark_a * ark_b));
+            prop_assert_eq!(-a, BFieldElement::from(-ark_a));
+            prop_assert_eq!(a.try_inverse(), ark_a.inverse().map(BFieldElement::from));
+        }
+    }
+}
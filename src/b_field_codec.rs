@@ -0,0 +1,374 @@
+use num_traits::ConstOne;
+use num_traits::ConstZero;
+
+use crate::b_field_element::BFieldElement;
+use crate::digest::Digest;
+
+/// Encoding and decoding of values as sequences of [`BFieldElement`]s, for example to
+/// [hash](crate::Tip5::hash) them.
+///
+/// The encoding must be injective, and [`decode`](Self::decode) must invert
+/// [`encode`](Self::encode). If all values of a type encode to sequences of the same length,
+/// that length is the [static length](Self::static_length).
+///
+/// The implementations of this crate encode
+/// - [`BFieldElement`]s as themselves,
+/// - [`Digest`]s as their five elements,
+/// - `bool`s as 0 or 1,
+/// - `u64`s and `u128`s as their 32-bit limbs, least significant first, like
+///   [`encode_u64s`](crate::encode_u64s) and [`encode_u128s`](crate::encode_u128s), and
+/// - `Vec`s as their length, followed by the encoded items. Items without a static length
+///   are each prefixed with the length of their encoding.
+pub trait BFieldCodec: Sized {
+    fn encode(&self) -> Vec<BFieldElement>;
+
+    fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError>;
+
+    /// The length of the encoding of every value of this type, if it is the same for all of
+    /// them, else `None`.
+    fn static_length() -> Option<usize>;
+}
+
+/// The error returned when [decoding](BFieldCodec::decode) fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BFieldCodecError {
+    /// The sequence has length `actual`, but the type has static length `expected`.
+    InvalidLength { expected: usize, actual: usize },
+
+    /// The sequence ends before the value is complete.
+    SequenceTooShort,
+
+    /// The sequence has elements left after the value is complete.
+    SequenceTooLong,
+
+    /// The element is not a valid encoding at its position, e.g., a `bool` other than 0 or 1,
+    /// or a limb of an integer that does not fit into 32 bits.
+    InvalidElement(BFieldElement),
+}
+
+impl std::fmt::Display for BFieldCodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength { expected, actual } => {
+                write!(f, "sequence must have length {expected}, got {actual}")
+            }
+            Self::SequenceTooShort => write!(f, "sequence is too short"),
+            Self::SequenceTooLong => write!(f, "sequence is too long"),
+            Self::InvalidElement(element) => write!(f, "invalid element {element}"),
+        }
+    }
+}
+
+impl std::error::Error for BFieldCodecError {}
+
+fn static_sequence<const N: usize>(
+    sequence: &[BFieldElement],
+) -> Result<[BFieldElement; N], BFieldCodecError> {
+    sequence
+        .try_into()
+        .map_err(|_| BFieldCodecError::InvalidLength {
+            expected: N,
+            actual: sequence.len(),
+        })
+}
+
+fn decode_u32_limbs<const N: usize>(sequence: &[BFieldElement]) -> Result<u128, BFieldCodecError> {
+    let limbs = static_sequence::<N>(sequence)?;
+    if let Some(&limb) = limbs.iter().find(|limb| limb.value() > u32::MAX.into()) {
+        return Err(BFieldCodecError::InvalidElement(limb));
+    }
+
+    let value = limbs
+        .iter()
+        .rev()
+        .fold(0, |acc, limb| (acc << 32) | u128::from(limb.value()));
+    Ok(value)
+}
+
+impl BFieldCodec for BFieldElement {
+    fn encode(&self) -> Vec<BFieldElement> {
+        vec![*self]
+    }
+
+    fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError> {
+        let [element] = static_sequence(sequence)?;
+        Ok(element)
+    }
+
+    fn static_length() -> Option<usize> {
+        Some(1)
+    }
+}
+
+impl BFieldCodec for Digest {
+    fn encode(&self) -> Vec<BFieldElement> {
+        self.values().to_vec()
+    }
+
+    fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError> {
+        Ok(Self::new(static_sequence(sequence)?))
+    }
+
+    fn static_length() -> Option<usize> {
+        Some(Self::LEN)
+    }
+}
+
+impl BFieldCodec for bool {
+    fn encode(&self) -> Vec<BFieldElement> {
+        vec![BFieldElement::from(*self)]
+    }
+
+    fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError> {
+        match static_sequence(sequence)? {
+            [BFieldElement::ZERO] => Ok(false),
+            [BFieldElement::ONE] => Ok(true),
+            [other] => Err(BFieldCodecError::InvalidElement(other)),
+        }
+    }
+
+    fn static_length() -> Option<usize> {
+        Some(1)
+    }
+}
+
+impl BFieldCodec for u64 {
+    fn encode(&self) -> Vec<BFieldElement> {
+        crate::encode_u64s(&[*self])
+    }
+
+    fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError> {
+        decode_u32_limbs::<2>(sequence).map(|value| value as u64)
+    }
+
+    fn static_length() -> Option<usize> {
+        Some(2)
+    }
+}
+
+impl BFieldCodec for u128 {
+    fn encode(&self) -> Vec<BFieldElement> {
+        crate::encode_u128s(&[*self])
+    }
+
+    fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError> {
+        decode_u32_limbs::<4>(sequence)
+    }
+
+    fn static_length() -> Option<usize> {
+        Some(4)
+    }
+}
+
+impl<T: BFieldCodec> BFieldCodec for Vec<T> {
+    fn encode(&self) -> Vec<BFieldElement> {
+        let mut sequence = vec![BFieldElement::new(self.len() as u64)];
+        for item in self {
+            let encoding = item.encode();
+            if T::static_length().is_none() {
+                sequence.push(BFieldElement::new(encoding.len() as u64));
+            }
+            sequence.extend(encoding);
+        }
+        sequence
+    }
+
+    fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError> {
+        let (len, mut sequence) = take_length(sequence)?;
+        let mut items = Vec::with_capacity(len.min(sequence.len()));
+        for _ in 0..len {
+            let item_len = match T::static_length() {
+                Some(item_len) => item_len,
+                None => {
+                    let (item_len, rest) = take_length(sequence)?;
+                    sequence = rest;
+                    item_len
+                }
+            };
+            if sequence.len() < item_len {
+                return Err(BFieldCodecError::SequenceTooShort);
+            }
+            let (item, rest) = sequence.split_at(item_len);
+            items.push(T::decode(item)?);
+            sequence = rest;
+        }
+
+        if !sequence.is_empty() {
+            return Err(BFieldCodecError::SequenceTooLong);
+        }
+        Ok(items)
+    }
+
+    fn static_length() -> Option<usize> {
+        None
+    }
+}
+
+/// Split off the length indicator at the start of the sequence.
+fn take_length(sequence: &[BFieldElement]) -> Result<(usize, &[BFieldElement]), BFieldCodecError> {
+    let (&len, rest) = sequence
+        .split_first()
+        .ok_or(BFieldCodecError::SequenceTooShort)?;
+    let len = usize::try_from(len.value()).map_err(|_| BFieldCodecError::InvalidElement(len))?;
+    Ok((len, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::Tip5;
+    use crate::bfe_array;
+    use crate::bfe_vec;
+
+    fn assert_round_trip<T: BFieldCodec + PartialEq + std::fmt::Debug>(value: T) {
+        let encoding = value.encode();
+        if let Some(static_length) = T::static_length() {
+            assert_eq!(static_length, encoding.len());
+        }
+        assert_eq!(value, T::decode(&encoding).unwrap());
+    }
+
+    #[test]
+    fn encoding_vectors() {
+        assert_eq!(bfe_vec![7], BFieldElement::new(7).encode());
+        assert_eq!(bfe_vec![0], false.encode());
+        assert_eq!(bfe_vec![1], true.encode());
+        assert_eq!(
+            bfe_vec![0x89ab_cdef, 0x0123_4567],
+            0x0123_4567_89ab_cdef_u64.encode()
+        );
+        assert_eq!(bfe_vec![1, 0, 0, 1], (1_u128 | (1 << 96)).encode());
+        assert_eq!(bfe_vec![2, 3, 0, 4, 0], vec![3_u64, 4].encode());
+        assert_eq!(
+            bfe_vec![2, 1, 0, 2, 1, 1],
+            vec![vec![], vec![true]].encode()
+        );
+    }
+
+    #[test]
+    fn values_round_trip() {
+        assert_round_trip(BFieldElement::new(42));
+        assert_round_trip(Digest::new_from_u64s([1, 2, 3, 4, 5]));
+        assert_round_trip(false);
+        assert_round_trip(true);
+        assert_round_trip(u64::MAX);
+        assert_round_trip(BFieldElement::P);
+        assert_round_trip(u128::MAX);
+        assert_round_trip(Vec::<u64>::new());
+        assert_round_trip(vec![
+            Digest::ALL_ZEROS,
+            Digest::new_from_u64s([1, 2, 3, 4, 5]),
+        ]);
+        assert_round_trip(vec![vec![], vec![1_u128], vec![2, 3]]);
+        assert_round_trip(vec![vec![vec![true]], vec![]]);
+    }
+
+    #[test]
+    fn decoding_invalid_sequences_fails() {
+        let expected = BFieldCodecError::InvalidLength {
+            expected: 5,
+            actual: 4,
+        };
+        assert_eq!(Err(expected), Digest::decode(&bfe_array![1, 2, 3, 4]));
+        assert_eq!(
+            Err(BFieldCodecError::InvalidElement(BFieldElement::new(2))),
+            bool::decode(&bfe_array![2])
+        );
+        assert_eq!(
+            Err(BFieldCodecError::InvalidElement(BFieldElement::new(
+                1 << 32
+            ))),
+            u64::decode(&bfe_array![0, 1 << 32])
+        );
+
+        assert_eq!(
+            Err(BFieldCodecError::SequenceTooShort),
+            Vec::<u64>::decode(&[])
+        );
+        assert_eq!(
+            Err(BFieldCodecError::SequenceTooShort),
+            Vec::<u64>::decode(&bfe_array![2, 1, 0, 1])
+        );
+        assert_eq!(
+            Err(BFieldCodecError::SequenceTooLong),
+            Vec::<u64>::decode(&bfe_array![1, 1, 0, 1])
+        );
+        assert_eq!(
+            Err(BFieldCodecError::SequenceTooShort),
+            Vec::<Vec<bool>>::decode(&bfe_array![1, 3, 1, 1])
+        );
+    }
+
+    /// A type with static length 10, like the input to [`Tip5::hash_pair`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct DigestPair {
+        left: Digest,
+        right: Digest,
+    }
+
+    impl BFieldCodec for DigestPair {
+        fn encode(&self) -> Vec<BFieldElement> {
+            [self.left.values(), self.right.values()].concat()
+        }
+
+        fn decode(sequence: &[BFieldElement]) -> Result<Self, BFieldCodecError> {
+            let elements = static_sequence::<10>(sequence)?;
+            let left = Digest::decode(&elements[..Digest::LEN])?;
+            let right = Digest::decode(&elements[Digest::LEN..])?;
+            Ok(Self { left, right })
+        }
+
+        fn static_length() -> Option<usize> {
+            Some(2 * Digest::LEN)
+        }
+    }
+
+    #[test]
+    fn hash_of_digest_pair_is_stable() {
+        let pair = DigestPair {
+            left: Digest::new_from_u64s([1, 2, 3, 4, 5]),
+            right: Digest::new_from_u64s([6, 7, 8, 9, 10]),
+        };
+        assert_round_trip(pair);
+        assert_eq!(Tip5::hash_pair(pair.left, pair.right), Tip5::hash(&pair));
+        assert_ne!(Tip5::hash_varlen(&pair.encode()), Tip5::hash(&pair));
+
+        let expected = Digest::new_from_u64s([
+            2408804056820506295,
+            10652515834329817520,
+            9332089110375976338,
+            9108330348717034295,
+            4330458751862606552,
+        ]);
+        assert_eq!(expected, Tip5::hash(&pair));
+    }
+
+    #[test]
+    fn hash_of_dynamically_sized_value_uses_varlen_hashing() {
+        let value = vec![1_u64, 2, 3];
+        assert_eq!(Tip5::hash_varlen(&value.encode()), Tip5::hash(&value));
+
+        let value = 42_u64;
+        assert_eq!(Tip5::hash_varlen(&value.encode()), Tip5::hash(&value));
+    }
+
+    proptest! {
+        #[test]
+        fn u64s_round_trip(values: Vec<u64>) {
+            assert_round_trip(values);
+        }
+
+        #[test]
+        fn nested_u128s_round_trip(values: Vec<Vec<u128>>) {
+            assert_round_trip(values);
+        }
+
+        #[test]
+        fn elements_round_trip(values in prop::collection::vec(0..BFieldElement::P, 0..20)) {
+            assert_round_trip(values.into_iter().map(BFieldElement::new).collect_vec());
+        }
+    }
+}
@@ -1,12 +1,77 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::num::IntErrorKind;
 use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
 
-use num_traits::{ConstOne, ConstZero, One, Zero};
+use num_traits::{ConstOne, ConstZero, FromPrimitive, Inv, One, Pow, ToPrimitive, Zero};
+
+/// Construct a [`BFieldElement`] from an integer expression of type `i128`. Negative values
+/// map to their additive inverse. Usable in `const` contexts.
+///
+/// ```
+/// # use tip5_hash::{BFieldElement, bfe};
+/// const MINUS_ONE: BFieldElement = bfe!(-1);
+/// assert_eq!(BFieldElement::new(BFieldElement::MAX), MINUS_ONE);
+/// assert_eq!(BFieldElement::new(1 << 40), bfe!(1 << 40));
+/// ```
+#[macro_export]
+macro_rules! bfe {
+    ($value:expr) => {{
+        let value: i128 = $value;
+        $crate::BFieldElement::from_i128(value)
+    }};
+}
+
+/// Construct an array of [`BFieldElement`]s, accepting the same expressions as [`bfe!`].
+///
+/// ```
+/// # use tip5_hash::{BFieldElement, bfe, bfe_array};
+/// assert_eq!([bfe!(1), bfe!(-2), bfe!(3)], bfe_array![1, -2, 3]);
+/// assert_eq!([bfe!(7); 4], bfe_array![7; 4]);
+/// ```
+#[macro_export]
+macro_rules! bfe_array {
+    () => {{
+        let empty: [$crate::BFieldElement; 0] = [];
+        empty
+    }};
+    ($value:expr; $length:expr) => {
+        [$crate::bfe!($value); $length]
+    };
+    ($($value:expr),* $(,)?) => {
+        [$($crate::bfe!($value)),*]
+    };
+}
+
+/// Construct a [`Vec`] of [`BFieldElement`]s, accepting the same expressions as [`bfe!`].
+///
+/// ```
+/// # use tip5_hash::{BFieldElement, bfe, bfe_vec};
+/// assert_eq!(vec![bfe!(1), bfe!(-2), bfe!(3)], bfe_vec![1, -2, 3]);
+/// assert_eq!(vec![bfe!(7); 4], bfe_vec![7; 4]);
+/// ```
+#[macro_export]
+macro_rules! bfe_vec {
+    () => {
+        ::std::vec::Vec::<$crate::BFieldElement>::new()
+    };
+    ($value:expr; $length:expr) => {
+        vec![$crate::bfe!($value); $length]
+    };
+    ($($value:expr),* $(,)?) => {
+        vec![$($crate::bfe!($value)),*]
+    };
+}
 
 /// Base field element ∈ ℤ_{2^64 - 2^32 + 1}.
 ///
 /// In Montgomery representation. This implementation follows <https://eprint.iacr.org/2022/274.pdf>
 /// and <https://github.com/novifinancial/winterfell/pull/101/files>.
 #[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq)]
+#[repr(transparent)]
 pub struct BFieldElement(u64);
 
 impl BFieldElement {
@@ -19,11 +84,88 @@ impl BFieldElement {
     /// 2^128 mod P; this is used for conversion of elements into Montgomery representation.
     const R2: u64 = 0xffff_fffe_0000_0001;
 
+    /// A generator of the multiplicative group of the field, which has order P-1.
+    pub const GENERATOR: Self = Self::new(7);
+
+    /// The largest k such that 2^k divides P-1.
+    pub const TWO_ADICITY: u32 = 32;
+
+    /// A primitive 2^[`TWO_ADICITY`](Self::TWO_ADICITY)-th root of unity, namely
+    /// [`GENERATOR`](Self::GENERATOR)^((P-1)/2^32). All other roots of unity of power-of-two
+    /// order are derived from it.
+    pub const TWO_ADIC_ROOT_OF_UNITY: Self = Self::new(1_753_635_133_440_165_772);
+
+    /// Create a new field element from the given value, reducing it modulo [`P`](Self::P).
+    ///
+    /// Values that are not smaller than `P` are silently reduced. Use the [`TryFrom<u64>`]
+    /// implementation to reject them instead, for example when the value stems from untrusted
+    /// input.
     #[inline]
     pub const fn new(value: u64) -> Self {
         Self(Self::montyred((value as u128) * (Self::R2 as u128)))
     }
 
+    /// [`ONE`](Self::ONE) for `true`, [`ZERO`](Self::ZERO) for `false`. Like the [`From`]
+    /// implementation, but usable in `const` contexts.
+    #[inline]
+    pub const fn from_bool(value: bool) -> Self {
+        Self::new(value as u64)
+    }
+
+    /// Create a new field element from the given value. Every u32 is smaller than
+    /// [`P`](Self::P), so no reduction takes place. Like the [`From`] implementation, but
+    /// usable in `const` contexts.
+    #[inline]
+    pub const fn from_u32(value: u32) -> Self {
+        Self::new(value as u64)
+    }
+
+    /// Create a new field element from the given signed value, reducing it modulo
+    /// [`P`](Self::P). Negative values map to their additive inverse.
+    ///
+    /// Unlike the [`From`] implementations, this function can be used in `const` contexts,
+    /// which is what the [`bfe!`](crate::bfe) macro relies on.
+    #[inline]
+    pub const fn from_i128(value: i128) -> Self {
+        let reduced = Self::from_u128(value.unsigned_abs());
+        if value < 0 {
+            reduced.const_neg()
+        } else {
+            reduced
+        }
+    }
+
+    /// Create a new field element from the given value, reducing it modulo [`P`](Self::P).
+    ///
+    /// The reduction exploits the special form of the prime: with 2^64 ≡ 2^32 - 1 and
+    /// 2^96 ≡ -1, a value lo + 2^64·hi_lo + 2^96·hi_hi is congruent to
+    /// lo + (2^32 - 1)·hi_lo - hi_hi. This makes the function suitable for reducing
+    /// accumulated 128-bit sums of products, for example.
+    #[inline]
+    pub const fn from_u128(value: u128) -> Self {
+        const EPSILON: u64 = (1 << 32) - 1;
+
+        let lo = value as u64;
+        let hi = (value >> 64) as u64;
+        let hi_hi = hi >> 32;
+        let hi_lo = hi & EPSILON;
+
+        // lo - 2^96·hi_hi; on borrow, the wrapped result is too large by 2^64 ≡ EPSILON
+        let (mut acc, borrow) = lo.overflowing_sub(hi_hi);
+        if borrow {
+            acc = acc.wrapping_sub(EPSILON);
+        }
+
+        // acc + 2^64·hi_lo; the product fits into a u64, and on carry, the wrapped result is
+        // too small by 2^64 ≡ EPSILON
+        let (mut acc, carry) = acc.overflowing_add(hi_lo * EPSILON);
+        if carry {
+            acc = acc.wrapping_add(EPSILON);
+        }
+
+        Self::new(acc)
+    }
+
     /// Montgomery reduction
     #[inline(always)]
     pub const fn montyred(x: u128) -> u64 {
@@ -50,12 +192,81 @@ impl BFieldElement {
     const fn canonical_representation(&self) -> u64 {
         Self::montyred(self.0 as u128)
     }
+
+    /// Field addition, usable in `const` contexts. Equivalent to the [`Add`] operator.
+    #[inline(always)]
+    pub const fn const_add(self, rhs: Self) -> Self {
+        // Compute a + b = a - (p - b).
+        let (x1, c1) = self.0.overflowing_sub(Self::P - rhs.0);
+
+        // The following if/else is equivalent to the commented-out code below but
+        // the if/else was found to be faster.
+        // let adj = 0u32.wrapping_sub(c1 as u32);
+        // Self(x1.wrapping_sub(adj as u64))
+        // See
+        // https://github.com/Neptune-Crypto/twenty-first/pull/70
+        if c1 {
+            Self(x1.wrapping_add(Self::P))
+        } else {
+            Self(x1)
+        }
+    }
+
+    /// Field subtraction, usable in `const` contexts. Equivalent to the [`Sub`] operator.
+    #[inline]
+    pub const fn const_sub(self, rhs: Self) -> Self {
+        let (x1, c1) = self.0.overflowing_sub(rhs.0);
+
+        // The following code is equivalent to the commented-out code below
+        // but they were determined to have near-equiavalent running times. Maybe because
+        // subtraction is not used very often.
+        // See: https://github.com/Neptune-Crypto/twenty-first/pull/70
+        // 1st alternative:
+        // if c1 {
+        //     Self(x1.wrapping_add(Self::P))
+        // } else {
+        //     Self(x1)
+        // }
+        // 2nd alternative:
+        // let adj = 0u32.wrapping_sub(c1 as u32);
+        // Self(x1.wrapping_sub(adj as u64))
+        Self(x1.wrapping_sub((1 + !Self::P) * c1 as u64))
+    }
+
+    /// Field multiplication, usable in `const` contexts. Equivalent to the [`Mul`] operator.
+    #[inline]
+    pub const fn const_mul(self, rhs: Self) -> Self {
+        Self(Self::montyred((self.0 as u128) * (rhs.0 as u128)))
+    }
+
+    /// Additive inverse, usable in `const` contexts. Equivalent to the [`Neg`] operator.
+    #[inline]
+    pub const fn const_neg(self) -> Self {
+        Self::ZERO.const_sub(self)
+    }
 }
 
 impl BFieldElement {
     #[must_use]
     #[inline]
     pub fn inverse(&self) -> Self {
+        assert_ne!(
+            *self,
+            Self::zero(),
+            "Attempted to find the multiplicative inverse of zero."
+        );
+
+        self.inverse_or_zero()
+    }
+
+    /// The multiplicative inverse of `self`, or [`ZERO`](Self::ZERO) if `self` is zero.
+    ///
+    /// In contrast to [`inverse`](Self::inverse), which panics on zero, this function is
+    /// total. It computes x^(P-2) with a fixed addition chain, which maps zero to zero without
+    /// any additional branching.
+    #[must_use]
+    #[inline]
+    pub fn inverse_or_zero(&self) -> Self {
         #[inline(always)]
         const fn exp(base: BFieldElement, exponent: u64) -> BFieldElement {
             let mut res = base;
@@ -68,12 +279,6 @@ impl BFieldElement {
         }
 
         let x = *self;
-        assert_ne!(
-            x,
-            Self::zero(),
-            "Attempted to find the multiplicative inverse of zero."
-        );
-
         let bin_2_ones = x.square() * x;
         let bin_3_ones = bin_2_ones.square() * x;
         let bin_6_ones = exp(bin_3_ones, 3) * bin_3_ones;
@@ -87,189 +292,3648 @@ impl BFieldElement {
         exp(bin_31_ones_1_zero, 32) * bin_32_ones
     }
 
-    #[inline(always)]
-    fn square(self) -> Self {
-        self * self
-    }
+    /// Raise `self` to a possibly negative power. A negative exponent -k means raising the
+    /// inverse of `self` to the power of k.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero and the exponent is negative. See
+    /// [`checked_pow_i64`](Self::checked_pow_i64) for a non-panicking alternative.
+    #[must_use]
+    #[inline]
+    pub fn pow_i64(&self, exponent: i64) -> Self {
+        let base = if exponent < 0 { self.inverse() } else { *self };
 
-    /// Return the raw bytes or 8-bit chunks of the Montgomery
-    /// representation, in little-endian byte order
-    pub const fn raw_bytes(&self) -> [u8; 8] {
-        self.0.to_le_bytes()
+        // `unsigned_abs` does not overflow for i64::MIN
+        base.mod_pow(exponent.unsigned_abs())
     }
 
+    /// Like [`pow_i64`](Self::pow_i64), but returns [`None`] instead of panicking if `self` is
+    /// zero and the exponent is negative.
+    #[must_use]
     #[inline]
-    pub const fn raw_u64(&self) -> u64 {
-        self.0
+    pub fn checked_pow_i64(&self, exponent: i64) -> Option<Self> {
+        let base = if exponent < 0 {
+            self.try_inverse()?
+        } else {
+            *self
+        };
+
+        Some(base.mod_pow(exponent.unsigned_abs()))
     }
 
-    /// Take a slice of 8 bytes and interpret it as an integer in
-    /// little-endian byte order, and cast it to a BFieldElement
-    /// in Montgomery representation
-    pub const fn from_raw_bytes(bytes: &[u8; 8]) -> Self {
-        Self(u64::from_le_bytes(*bytes))
+    /// A primitive root of unity of exactly the given `order`, or [`None`] if no such root is
+    /// supported. The supported orders are the powers of two up to and including 2^32.
+    ///
+    /// The returned root is deterministic: the root of order 2^k is
+    /// [`GENERATOR`](Self::GENERATOR)^((P-1)/2^k).
+    ///
+    /// ```
+    /// # use tip5_hash::BFieldElement;
+    /// # use num_traits::ConstOne;
+    /// let root = BFieldElement::primitive_root_of_unity(8).unwrap();
+    /// let domain = (0..8).map(|i| root.mod_pow(i)).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(BFieldElement::ONE, domain[0]);
+    /// assert_eq!(BFieldElement::ONE, domain[7] * root);
+    /// assert!(domain[1..].iter().all(|&x| x != BFieldElement::ONE));
+    /// ```
+    pub fn primitive_root_of_unity(order: u64) -> Option<Self> {
+        if !order.is_power_of_two() {
+            return None;
+        }
+        let log_2_of_order = order.trailing_zeros();
+        if log_2_of_order > Self::TWO_ADICITY {
+            return None;
+        }
+
+        let mut root = Self::TWO_ADIC_ROOT_OF_UNITY;
+        for _ in log_2_of_order..Self::TWO_ADICITY {
+            root = root.square();
+        }
+
+        Some(root)
     }
 
+    /// The Legendre symbol of `self`: 1 if `self` is a non-zero square, -1 if it is not a
+    /// square, and 0 if `self` is zero. Computed via Euler's criterion, x^((P-1)/2).
+    #[must_use]
+    pub fn legendre_symbol(&self) -> i8 {
+        let euler_criterion = self.mod_pow((Self::P - 1) / 2);
+        if euler_criterion.is_zero() {
+            0
+        } else if euler_criterion.is_one() {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Whether `self` is a square in the field. Zero is a square.
+    #[must_use]
     #[inline]
-    pub const fn from_raw_u64(e: u64) -> BFieldElement {
-        BFieldElement(e)
+    pub fn is_square(&self) -> bool {
+        self.legendre_symbol() >= 0
     }
-}
 
-impl Zero for BFieldElement {
+    /// A square root of `self`, or [`None`] if `self` is not a square.
+    ///
+    /// Of the two roots r and -r, the one with the smaller canonical value, _i.e._, the one
+    /// that is at most P/2, is returned. The square root of zero is zero.
+    ///
+    /// Uses the Tonelli–Shanks algorithm with P-1 = 2^32 · (2^32 - 1).
+    #[must_use]
+    pub fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::ZERO);
+        }
+
+        const ODD_PART: u64 = (BFieldElement::P - 1) >> BFieldElement::TWO_ADICITY;
+
+        // invariants: root^2 = self · t, c has order 2^max_order, t has order dividing 2^max_order
+        let mut max_order = Self::TWO_ADICITY;
+        let mut c = Self::TWO_ADIC_ROOT_OF_UNITY;
+        let mut t = self.mod_pow(ODD_PART);
+        let mut root = self.mod_pow(ODD_PART.div_ceil(2));
+
+        while !t.is_one() {
+            let mut order = 0;
+            let mut t_pow = t;
+            while !t_pow.is_one() {
+                t_pow = t_pow.square();
+                order += 1;
+            }
+            if order == max_order {
+                // t has order 2^TWO_ADICITY, _i.e._, `self` is not a square
+                return None;
+            }
+
+            let mut b = c;
+            for _ in 0..max_order - order - 1 {
+                b = b.square();
+            }
+            max_order = order;
+            c = b.square();
+            t *= c;
+            root *= b;
+        }
+
+        if root.value() > Self::P / 2 {
+            root = -root;
+        }
+
+        Some(root)
+    }
+
+    /// The multiplicative inverse of `self`, or [`None`] if `self` is zero.
+    #[must_use]
     #[inline]
-    fn zero() -> Self {
-        Self::ZERO
+    pub fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(self.inverse_or_zero())
+        }
     }
 
+    /// Divide `self` by `rhs`, returning [`None`] if `rhs` is zero.
+    ///
+    /// This is the non-panicking counterpart to the [`Div`] operator.
+    #[must_use]
     #[inline]
-    fn is_zero(&self) -> bool {
-        self == &Self::ZERO
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        rhs.try_inverse().map(|rhs_inverse| rhs_inverse * self)
     }
-}
 
-impl ConstZero for BFieldElement {
-    const ZERO: Self = Self::new(0);
-}
+    /// Invert every element of `elements` in place using Montgomery's trick: a single field
+    /// inversion and roughly 3·n multiplications.
+    ///
+    /// Unlike [`inverse`](Self::inverse), this never panics: zero entries are left as zero and
+    /// do not affect the inversion of the other elements.
+    pub fn batch_inversion(elements: &mut [Self]) {
+        // `prefix_products[i]` is the product of all non-zero elements before index `i`.
+        let mut prefix_products = Vec::with_capacity(elements.len());
+        let mut acc = Self::ONE;
+        for &element in elements.iter() {
+            prefix_products.push(acc);
+            if !element.is_zero() {
+                acc *= element;
+            }
+        }
 
-impl One for BFieldElement {
+        // `acc` is a product of non-zero elements and consequently non-zero itself
+        let mut acc_inverse = acc.inverse();
+        for (element, prefix_product) in elements.iter_mut().zip(prefix_products).rev() {
+            if element.is_zero() {
+                continue;
+            }
+            let next_acc_inverse = acc_inverse * *element;
+            *element = acc_inverse * prefix_product;
+            acc_inverse = next_acc_inverse;
+        }
+    }
+
+    /// Like [`batch_inversion`](Self::batch_inversion), but leaves the input untouched and
+    /// returns the inverses instead.
+    #[must_use]
+    pub fn batch_inverse(elements: &[Self]) -> Vec<Self> {
+        let mut inverses = elements.to_vec();
+        Self::batch_inversion(&mut inverses);
+        inverses
+    }
+
+    #[inline(always)]
+    const fn square(self) -> Self {
+        self.const_mul(self)
+    }
+
+    /// Multiply by a small scalar.
+    ///
+    /// Equivalent to `self * BFieldElement::from(k)`, but faster: the scalar is not converted
+    /// into Montgomery representation. Instead, the product of the raw word and `k`, which is
+    /// smaller than 2^96, is reduced directly using 2^64 ≡ 2^32 - 1 (mod P).
+    #[must_use]
     #[inline]
-    fn one() -> Self {
-        Self::ONE
+    pub const fn mul_u32(self, k: u32) -> Self {
+        const EPSILON: u64 = (1 << 32) - 1;
+
+        let product = self.0 as u128 * k as u128;
+        let lo = product as u64;
+        let hi = (product >> 64) as u64;
+
+        // hi < 2^32, hence hi·(2^32 - 1) < 2^64
+        let (sum, overflow) = lo.overflowing_add(hi * EPSILON);
+        let sum = if overflow { sum + EPSILON } else { sum };
+        let sum = if sum >= Self::P { sum - Self::P } else { sum };
+
+        Self(sum)
     }
 
+    /// Divide by two, exactly.
+    ///
+    /// Since the Montgomery representation is linear, halving it halves the represented value.
+    /// An even raw word is shifted right; an odd one is made even by adding P first.
+    #[must_use]
     #[inline]
-    fn is_one(&self) -> bool {
-        self == &Self::ONE
+    pub const fn halve(&self) -> Self {
+        if self.0 & 1 == 0 {
+            Self(self.0 >> 1)
+        } else {
+            // (a + P) / 2 without overflowing, where both a and P are odd
+            Self((self.0 >> 1) + (Self::P >> 1) + 1)
+        }
     }
-}
 
-impl ConstOne for BFieldElement {
-    const ONE: Self = Self::new(1);
-}
+    /// Raise `self` to the power of `exponent` using square-and-multiply.
+    ///
+    /// By convention, `x.mod_pow(0)` is [`ONE`](Self::ONE) for every `x`, including
+    /// [`ZERO`](Self::ZERO).
+    ///
+    /// The running time depends on the bit length and Hamming weight of the exponent. Use
+    /// [`mod_pow_ct`](Self::mod_pow_ct) if the exponent is secret.
+    #[must_use]
+    #[inline]
+    pub fn mod_pow(&self, exponent: u64) -> Self {
+        let mut acc = Self::ONE;
+        let bit_length = u64::BITS - exponent.leading_zeros();
+        for i in (0..bit_length).rev() {
+            acc = acc.square();
+            if exponent & (1 << i) != 0 {
+                acc *= *self;
+            }
+        }
 
-impl Add for BFieldElement {
-    type Output = Self;
+        acc
+    }
 
-    #[allow(clippy::suspicious_arithmetic_impl)]
-    #[inline(always)]
-    fn add(self, rhs: Self) -> Self {
-        // Compute a + b = a - (p - b).
-        let (x1, c1) = self.0.overflowing_sub(Self::P - rhs.0);
+    /// Raise `self` to the power of `exponent` in constant time.
+    ///
+    /// Uses a Montgomery ladder over all 64 bits of the exponent with branch-free conditional
+    /// swaps, so that the sequence of operations is independent of both the base and the
+    /// exponent. The result is identical to [`mod_pow`](Self::mod_pow).
+    ///
+    /// Regarding timing side channels, the crate provides the following guarantees:
+    /// - [`mod_pow_ct`](Self::mod_pow_ct) is constant-time in both arguments.
+    /// - [`inverse_or_zero`](Self::inverse_or_zero) uses a fixed addition chain and is
+    ///   constant-time in its argument. [`inverse`](Self::inverse) additionally branches on
+    ///   whether the argument is zero.
+    /// - [`mod_pow`](Self::mod_pow), [`mod_pow_u32`](Self::mod_pow_u32),
+    ///   [`pow_i64`](Self::pow_i64), and [`sqrt`](Self::sqrt) are variable-time.
+    /// - Field multiplication and subtraction are branch-free. Field addition contains a
+    ///   data-dependent conditional, which compilers typically lower to a conditional move.
+    #[must_use]
+    pub fn mod_pow_ct(&self, exponent: u64) -> Self {
+        /// Swap `a` and `b` if `swap` is 1, leave them untouched if it is 0.
+        #[inline(always)]
+        fn conditional_swap(a: &mut BFieldElement, b: &mut BFieldElement, swap: u64) {
+            let mask = std::hint::black_box(0_u64.wrapping_sub(swap));
+            let difference = mask & (a.0 ^ b.0);
+            a.0 ^= difference;
+            b.0 ^= difference;
+        }
 
-        // The following if/else is equivalent to the commented-out code below but
-        // the if/else was found to be faster.
-        // let adj = 0u32.wrapping_sub(c1 as u32);
-        // Self(x1.wrapping_sub(adj as u64))
-        // See
-        // https://github.com/Neptune-Crypto/twenty-first/pull/70
-        if c1 {
-            Self(x1.wrapping_add(Self::P))
-        } else {
-            Self(x1)
+        // invariant: high == low · self
+        let mut low = Self::ONE;
+        let mut high = *self;
+        for i in (0..u64::BITS).rev() {
+            let bit = (exponent >> i) & 1;
+            conditional_swap(&mut low, &mut high, bit);
+            high *= low;
+            low = low.square();
+            conditional_swap(&mut low, &mut high, bit);
         }
+
+        low
     }
-}
 
-impl AddAssign for BFieldElement {
-    #[inline(always)]
-    fn add_assign(&mut self, rhs: Self) {
-        *self = *self + rhs
+    /// The infinite sequence of powers of `self`: 1, `self`, `self`², …
+    ///
+    /// Every item is computed with one multiplication from its predecessor.
+    pub fn powers(&self) -> impl Iterator<Item = Self> + use<> {
+        let base = *self;
+        std::iter::successors(Some(Self::ONE), move |&power| Some(power * base))
     }
-}
 
-impl SubAssign for BFieldElement {
+    /// The first `n` [powers](Self::powers) of `base`, _i.e._, 1, `base`, …, `base`^(n-1).
+    pub fn powers_of(base: Self, n: usize) -> Vec<Self> {
+        base.powers().take(n).collect()
+    }
+
+    /// The `n` elements `offset`·`generator`^i for i in 0..n. With a
+    /// [primitive root of unity](Self::primitive_root_of_unity) as the `generator`, this is a
+    /// coset of a multiplicative subgroup, as used for low-degree extensions.
+    pub fn coset_domain(offset: Self, generator: Self, n: usize) -> Vec<Self> {
+        generator
+            .powers()
+            .take(n)
+            .map(|power| offset * power)
+            .collect()
+    }
+
+    /// Raise `self` to the power of `exponent`. Like [`mod_pow`](Self::mod_pow), but
+    /// the shorter exponent means at most 32 squarings.
+    #[must_use]
     #[inline]
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs
+    pub fn mod_pow_u32(&self, exponent: u32) -> Self {
+        let mut acc = Self::ONE;
+        let bit_length = u32::BITS - exponent.leading_zeros();
+        for i in (0..bit_length).rev() {
+            acc = acc.square();
+            if exponent & (1 << i) != 0 {
+                acc *= *self;
+            }
+        }
+
+        acc
     }
-}
 
-impl MulAssign for BFieldElement {
+    /// The canonical [value](Self::value) in little-endian byte order.
+    ///
+    /// Together with [`from_le_bytes`](Self::from_le_bytes), this is the encoding to use for
+    /// persistence and over the wire. In contrast, [`raw_bytes`](Self::raw_bytes) exposes the
+    /// internal Montgomery representation, which is an implementation detail.
     #[inline]
-    fn mul_assign(&mut self, rhs: Self) {
-        *self = *self * rhs;
+    pub const fn to_le_bytes(&self) -> [u8; 8] {
+        self.value().to_le_bytes()
     }
-}
 
-impl Mul for BFieldElement {
-    type Output = Self;
+    /// The canonical [value](Self::value) in big-endian byte order. See
+    /// [`to_le_bytes`](Self::to_le_bytes).
+    #[inline]
+    pub const fn to_be_bytes(&self) -> [u8; 8] {
+        self.value().to_be_bytes()
+    }
 
+    /// Decode a canonical value from little-endian bytes, as produced by
+    /// [`to_le_bytes`](Self::to_le_bytes). Values that are not smaller than [`P`](Self::P)
+    /// are rejected rather than reduced.
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        Self(Self::montyred((self.0 as u128) * (rhs.0 as u128)))
+    pub const fn from_le_bytes(bytes: &[u8; 8]) -> Result<Self, NotCanonicalError> {
+        Self::from_canonical_u64(u64::from_le_bytes(*bytes))
     }
-}
 
-impl Neg for BFieldElement {
-    type Output = Self;
+    /// Decode a canonical value from big-endian bytes, as produced by
+    /// [`to_be_bytes`](Self::to_be_bytes). Values that are not smaller than [`P`](Self::P)
+    /// are rejected rather than reduced.
+    #[inline]
+    pub const fn from_be_bytes(bytes: &[u8; 8]) -> Result<Self, NotCanonicalError> {
+        Self::from_canonical_u64(u64::from_be_bytes(*bytes))
+    }
 
     #[inline]
-    fn neg(self) -> Self {
-        Self::zero() - self
+    const fn from_canonical_u64(value: u64) -> Result<Self, NotCanonicalError> {
+        if value < Self::P {
+            Ok(Self::new(value))
+        } else {
+            Err(NotCanonicalError(value))
+        }
     }
-}
 
-impl Sub for BFieldElement {
-    type Output = Self;
+    /// The low and high 32 bits of the canonical [value](Self::value), in that order.
+    ///
+    /// See [`split_raw_lo_hi`](Self::split_raw_lo_hi) for the decomposition of the internal
+    /// Montgomery representation.
+    #[inline]
+    pub const fn split_lo_hi(&self) -> (u32, u32) {
+        let value = self.value();
+        (value as u32, (value >> 32) as u32)
+    }
 
-    #[allow(clippy::suspicious_arithmetic_impl)]
+    /// Create a field element from the low and high 32 bits of its value, reducing the
+    /// value hi·2^32 + lo modulo [`P`](Self::P). This is the inverse of
+    /// [`split_lo_hi`](Self::split_lo_hi).
     #[inline]
-    fn sub(self, rhs: Self) -> Self {
-        let (x1, c1) = self.0.overflowing_sub(rhs.0);
+    pub const fn from_lo_hi(lo: u32, hi: u32) -> Self {
+        Self::new(((hi as u64) << 32) | lo as u64)
+    }
 
-        // The following code is equivalent to the commented-out code below
-        // but they were determined to have near-equiavalent running times. Maybe because
-        // subtraction is not used very often.
-        // See: https://github.com/Neptune-Crypto/twenty-first/pull/70
-        // 1st alternative:
-        // if c1 {
-        //     Self(x1.wrapping_add(Self::P))
-        // } else {
-        //     Self(x1)
-        // }
-        // 2nd alternative:
-        // let adj = 0u32.wrapping_sub(c1 as u32);
-        // Self(x1.wrapping_sub(adj as u64))
-        Self(x1.wrapping_sub((1 + !Self::P) * c1 as u64))
+    /// The low and high 32 bits of the internal Montgomery representation, in that order.
+    ///
+    /// This agrees with [`split_lo_hi`](Self::split_lo_hi) applied to the element
+    /// `BFieldElement::new(self.raw_u64())`, and generally differs from the decomposition of
+    /// the canonical value.
+    #[inline]
+    pub const fn split_raw_lo_hi(&self) -> (u32, u32) {
+        (self.0 as u32, (self.0 >> 32) as u32)
+    }
+
+    /// Create a field element from the low and high 32 bits of its internal Montgomery
+    /// representation. This is the inverse of [`split_raw_lo_hi`](Self::split_raw_lo_hi).
+    ///
+    /// The same invariant as for [`from_raw_u64`](Self::from_raw_u64) applies.
+    #[inline]
+    pub const fn from_raw_lo_hi(lo: u32, hi: u32) -> Self {
+        Self::from_raw_u64(((hi as u64) << 32) | lo as u64)
+    }
+
+    /// The bits of the canonical [value](Self::value), least significant bit first.
+    pub fn to_bits(&self) -> [bool; 64] {
+        let value = self.value();
+        std::array::from_fn(|i| (value >> i) & 1 == 1)
+    }
+
+    /// The least significant `num_bits` bits of the canonical [value](Self::value), least
+    /// significant bit first. For `num_bits` greater than 64, the result is padded with
+    /// `false`.
+    ///
+    /// Fails if the value does not fit into `num_bits` bits, which makes this function
+    /// suitable for range-checked values.
+    pub fn to_bit_vec(&self, num_bits: usize) -> Result<Vec<bool>, BitDecompositionError> {
+        let value = self.value();
+        let bit_length = (u64::BITS - value.leading_zeros()) as usize;
+        if bit_length > num_bits {
+            return Err(BitDecompositionError::ValueTooLarge { value, num_bits });
+        }
+
+        let mut bits = self.to_bits().to_vec();
+        bits.resize(num_bits, false);
+        Ok(bits)
+    }
+
+    /// Reassemble a field element from the bits of its canonical value, least significant
+    /// bit first. Missing high bits are taken to be `false`.
+    ///
+    /// Fails if more than 64 bits are given or if the bits encode a value that is not smaller
+    /// than [`P`](Self::P).
+    pub fn from_bits(bits: &[bool]) -> Result<Self, BitDecompositionError> {
+        if bits.len() > u64::BITS as usize {
+            return Err(BitDecompositionError::TooManyBits(bits.len()));
+        }
+
+        let value = bits
+            .iter()
+            .enumerate()
+            .fold(0, |acc, (i, &bit)| acc | (u64::from(bit) << i));
+        Self::try_from(value)
+            .map_err(|NotCanonicalError(value)| BitDecompositionError::NotCanonical(value))
+    }
+
+    /// Return the raw bytes or 8-bit chunks of the Montgomery
+    /// representation, in little-endian byte order
+    ///
+    /// This is an internal representation; for persistence, use
+    /// [`to_le_bytes`](Self::to_le_bytes).
+    pub const fn raw_bytes(&self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    #[inline]
+    pub const fn raw_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Take a slice of 8 bytes and interpret it as an integer in
+    /// little-endian byte order, and cast it to a BFieldElement
+    /// in Montgomery representation
+    ///
+    /// The same invariant as for [`from_raw_u64`](Self::from_raw_u64) applies. See
+    /// [`try_from_raw_bytes`](Self::try_from_raw_bytes) for a checked alternative.
+    pub const fn from_raw_bytes(bytes: &[u8; 8]) -> Self {
+        Self(u64::from_le_bytes(*bytes))
+    }
+
+    /// Like [`from_raw_bytes`](Self::from_raw_bytes), but returns [`None`] if the bytes
+    /// do not encode a valid Montgomery representation.
+    pub const fn try_from_raw_bytes(bytes: &[u8; 8]) -> Option<Self> {
+        Self::try_from_raw_u64(u64::from_le_bytes(*bytes))
+    }
+
+    /// Interpret the given word as the Montgomery representation of a field element.
+    ///
+    /// The caller must make sure that the word is [canonical](Self::is_canonical_raw), _i.e._,
+    /// smaller than [`P`](Self::P). All arithmetic and the [`PartialEq`] implementation rely on
+    /// this invariant; violating it leads to incorrect results, though never to undefined
+    /// behavior. Use [`try_from_raw_u64`](Self::try_from_raw_u64) for words of unknown origin.
+    #[inline]
+    pub const fn from_raw_u64(e: u64) -> BFieldElement {
+        BFieldElement(e)
+    }
+
+    /// Like [`from_raw_u64`](Self::from_raw_u64), but returns [`None`] if the word is not
+    /// [canonical](Self::is_canonical_raw).
+    #[inline]
+    pub const fn try_from_raw_u64(raw: u64) -> Option<Self> {
+        if Self::is_canonical_raw(raw) {
+            Some(Self(raw))
+        } else {
+            None
+        }
+    }
+
+    /// Whether the given word is a valid Montgomery representation, _i.e._, smaller than
+    /// [`P`](Self::P).
+    #[inline]
+    pub const fn is_canonical_raw(raw: u64) -> bool {
+        raw < Self::P
+    }
+
+    /// View the given elements as their raw Montgomery representations, without copying.
+    #[inline]
+    pub const fn slice_as_raw_u64s(elements: &[Self]) -> &[u64] {
+        // SAFETY: `BFieldElement` is `repr(transparent)` over `u64`, so both types have the
+        // same size and alignment, and every element is a valid `u64`.
+        unsafe { core::slice::from_raw_parts(elements.as_ptr().cast(), elements.len()) }
+    }
+
+    /// Mutable counterpart of [`slice_as_raw_u64s`](Self::slice_as_raw_u64s).
+    ///
+    /// Words written through the returned slice must be
+    /// [canonical](Self::is_canonical_raw); see [`from_raw_u64`](Self::from_raw_u64).
+    #[inline]
+    pub const fn slice_as_raw_u64s_mut(elements: &mut [Self]) -> &mut [u64] {
+        // SAFETY: see `slice_as_raw_u64s`. Writing any `u64` is memory-safe.
+        unsafe { core::slice::from_raw_parts_mut(elements.as_mut_ptr().cast(), elements.len()) }
+    }
+
+    /// Interpret the given words as Montgomery representations of field elements, without
+    /// copying.
+    ///
+    /// The same invariant as for [`from_raw_u64`](Self::from_raw_u64) applies to every word.
+    /// Use [`try_slice_from_raw_u64s`](Self::try_slice_from_raw_u64s) for words of unknown
+    /// origin.
+    #[inline]
+    pub const fn slice_from_raw_u64s(raw: &[u64]) -> &[Self] {
+        // SAFETY: `BFieldElement` is `repr(transparent)` over `u64`, so both types have the
+        // same size and alignment, and every `u64` is a memory-safe `BFieldElement`.
+        unsafe { core::slice::from_raw_parts(raw.as_ptr().cast(), raw.len()) }
+    }
+
+    /// Mutable counterpart of [`slice_from_raw_u64s`](Self::slice_from_raw_u64s).
+    #[inline]
+    pub const fn slice_from_raw_u64s_mut(raw: &mut [u64]) -> &mut [Self] {
+        // SAFETY: see `slice_from_raw_u64s`
+        unsafe { core::slice::from_raw_parts_mut(raw.as_mut_ptr().cast(), raw.len()) }
+    }
+
+    /// Like [`slice_from_raw_u64s`](Self::slice_from_raw_u64s), but returns [`None`] if any
+    /// word is not [canonical](Self::is_canonical_raw).
+    pub const fn try_slice_from_raw_u64s(raw: &[u64]) -> Option<&[Self]> {
+        if Self::all_canonical_raw(raw) {
+            Some(Self::slice_from_raw_u64s(raw))
+        } else {
+            None
+        }
+    }
+
+    /// Like [`slice_from_raw_u64s_mut`](Self::slice_from_raw_u64s_mut), but returns [`None`]
+    /// if any word is not [canonical](Self::is_canonical_raw).
+    pub const fn try_slice_from_raw_u64s_mut(raw: &mut [u64]) -> Option<&mut [Self]> {
+        if Self::all_canonical_raw(raw) {
+            Some(Self::slice_from_raw_u64s_mut(raw))
+        } else {
+            None
+        }
+    }
+
+    /// Convert the given values into field elements, reducing modulo [`P`](Self::P). This
+    /// is equivalent to calling [`new`](Self::new) for every value, but avoids reallocation
+    /// and is amenable to auto-vectorization.
+    pub fn montify_slice(values: &[u64]) -> Vec<Self> {
+        // mapping an exact-size iterator without early exits lets the compiler vectorize
+        values.iter().map(|&value| Self::new(value)).collect()
+    }
+
+    /// Convert the given values into field elements in place, reducing modulo
+    /// [`P`](Self::P), and view the result as field elements without copying.
+    pub fn montify_in_place(values: &mut [u64]) -> &mut [Self] {
+        for value in values.iter_mut() {
+            *value = Self::new(*value).0;
+        }
+        Self::slice_from_raw_u64s_mut(values)
+    }
+
+    /// The canonical [values](Self::value) of the given elements. This is equivalent to
+    /// calling [`value`](Self::value) for every element, but avoids reallocation and is
+    /// amenable to auto-vectorization.
+    pub fn canonical_values(elements: &[Self]) -> Vec<u64> {
+        elements.iter().map(Self::value).collect()
+    }
+
+    const fn all_canonical_raw(raw: &[u64]) -> bool {
+        let mut i = 0;
+        while i < raw.len() {
+            if !Self::is_canonical_raw(raw[i]) {
+                return false;
+            }
+            i += 1;
+        }
+        true
     }
 }
 
-impl Div for BFieldElement {
+impl Zero for BFieldElement {
+    #[inline]
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self == &Self::ZERO
+    }
+}
+
+impl ConstZero for BFieldElement {
+    const ZERO: Self = Self::new(0);
+}
+
+impl One for BFieldElement {
+    #[inline]
+    fn one() -> Self {
+        Self::ONE
+    }
+
+    #[inline]
+    fn is_one(&self) -> bool {
+        self == &Self::ONE
+    }
+}
+
+impl ConstOne for BFieldElement {
+    const ONE: Self = Self::new(1);
+}
+
+impl Inv for BFieldElement {
     type Output = Self;
 
-    #[allow(clippy::suspicious_arithmetic_impl)]
-    fn div(self, other: Self) -> Self {
-        other.inverse() * self
+    /// See [`BFieldElement::inverse`]. Panics if `self` is zero.
+    #[inline]
+    fn inv(self) -> Self {
+        self.inverse()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_montgomery_reduction() {
-        let input = 2_609_026_890_597_981_882u128;
-        let expected = 11_259_563_268_822_605_859u64;
+impl Inv for &BFieldElement {
+    type Output = BFieldElement;
 
-        let result = BFieldElement::montyred(input);
+    /// See [`BFieldElement::inverse`]. Panics if `self` is zero.
+    #[inline]
+    fn inv(self) -> BFieldElement {
+        self.inverse()
+    }
+}
 
-        assert_eq!(result, expected, "Montgomery reduction failed");
+impl From<bool> for BFieldElement {
+    /// [`ONE`](BFieldElement::ONE) for `true`, [`ZERO`](BFieldElement::ZERO) for `false`.
+    #[inline]
+    fn from(value: bool) -> Self {
+        Self::from_bool(value)
     }
+}
 
-    #[test]
-    fn test_montify() {
-        let value = 12_045_832_659_793_544_965;
-        let bfe = BFieldElement::new(value);
-        let expected = 9_712_864_734_344_745_984u64;
+impl From<u8> for BFieldElement {
+    #[inline]
+    fn from(value: u8) -> Self {
+        Self::from_u32(value.into())
+    }
+}
 
-        let result = BFieldElement::new(value).0;
+impl From<u16> for BFieldElement {
+    #[inline]
+    fn from(value: u16) -> Self {
+        Self::from_u32(value.into())
+    }
+}
 
-        assert_eq!(result, expected, "Montification failed");
+/// Every u32 is smaller than [`BFieldElement::P`], so no reduction takes place.
+impl From<u32> for BFieldElement {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self::from_u32(value)
+    }
+}
 
-        let red = bfe.value();
-        assert_eq!(red, value, "Canonical representation failed");
+impl From<u128> for BFieldElement {
+    /// Reduces the value modulo [`BFieldElement::P`]. See [`BFieldElement::from_u128`].
+    #[inline]
+    fn from(value: u128) -> Self {
+        Self::from_u128(value)
+    }
+}
+
+/// Strict conversion: in contrast to [`BFieldElement::new`], values that are not smaller than
+/// [`BFieldElement::P`] are rejected rather than reduced.
+impl TryFrom<u64> for BFieldElement {
+    type Error = NotCanonicalError;
+
+    #[inline]
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        Self::from_canonical_u64(value)
+    }
+}
+
+/// Strict conversion: like with `TryFrom<u64>`, values that are not smaller than
+/// [`BFieldElement::P`] are rejected rather than reduced.
+///
+/// On targets where `usize` has at most 32 bits, this conversion never fails. For values
+/// known to fit into a `u32`, the infallible [`From<u32>`] conversion is preferable.
+impl TryFrom<usize> for BFieldElement {
+    type Error = NotCanonicalError;
+
+    #[inline]
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        // lossless: Rust does not support targets where usize is wider than 64 bits
+        Self::try_from(value as u64)
+    }
+}
+
+/// Negative values map to their additive inverse, _i.e._, -x becomes P - x.
+///
+/// ```
+/// # use tip5_hash::BFieldElement;
+/// # use num_traits::ConstOne;
+/// assert_eq!(-BFieldElement::ONE, BFieldElement::from(-1_i64));
+/// ```
+impl From<i64> for BFieldElement {
+    #[inline]
+    fn from(value: i64) -> Self {
+        // `unsigned_abs` does not overflow for i64::MIN
+        let magnitude = Self::new(value.unsigned_abs());
+        if value < 0 { -magnitude } else { magnitude }
+    }
+}
+
+/// Negative values map to their additive inverse, _i.e._, -x becomes P - x.
+impl From<i32> for BFieldElement {
+    #[inline]
+    fn from(value: i32) -> Self {
+        Self::from(i64::from(value))
+    }
+}
+
+impl From<BFieldElement> for u64 {
+    /// The canonical [value](BFieldElement::value).
+    #[inline]
+    fn from(element: BFieldElement) -> Self {
+        element.value()
+    }
+}
+
+macro_rules! impl_try_from_bfe_for_integer {
+    ($($t:ty),+ $(,)?) => {$(
+        /// Succeeds if the canonical [value](BFieldElement::value) fits into the target type.
+        ///
+        /// Negative integers are not recovered: the canonical value of `BFieldElement::from(-1)`
+        /// is P-1, which does not fit into any signed integer type of at most 64 bits.
+        impl TryFrom<BFieldElement> for $t {
+            type Error = TryFromBFieldElementError;
+
+            #[inline]
+            fn try_from(element: BFieldElement) -> Result<Self, Self::Error> {
+                let value = element.value();
+                <$t>::try_from(value).map_err(|_| TryFromBFieldElementError {
+                    value,
+                    target: stringify!($t),
+                })
+            }
+        }
+    )+};
+}
+
+impl_try_from_bfe_for_integer!(u8, u32, i64);
+
+/// All integer conversions succeed: values are reduced modulo [`BFieldElement::P`], and
+/// negative values map to their additive inverse. Floating-point values are truncated towards
+/// zero and converted if they fit into an `i64` or a `u64`.
+impl FromPrimitive for BFieldElement {
+    #[inline]
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(Self::from(n))
+    }
+
+    #[inline]
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(Self::new(n))
+    }
 
+    #[inline]
+    fn from_i128(n: i128) -> Option<Self> {
+        Some(BFieldElement::from_i128(n))
+    }
+
+    #[inline]
+    fn from_u128(n: u128) -> Option<Self> {
+        Some(Self::from_u128(n))
+    }
+}
+
+/// Converts the canonical [value](BFieldElement::value), returning [`None`] if it does not fit
+/// into the target type.
+impl ToPrimitive for BFieldElement {
+    #[inline]
+    fn to_i64(&self) -> Option<i64> {
+        i64::try_from(self.value()).ok()
+    }
+
+    #[inline]
+    fn to_u64(&self) -> Option<u64> {
+        Some(self.value())
+    }
+
+    #[inline]
+    fn to_i128(&self) -> Option<i128> {
+        Some(self.value().into())
+    }
+
+    #[inline]
+    fn to_u128(&self) -> Option<u128> {
+        Some(self.value().into())
+    }
+}
+
+/// Formats the canonical [value](BFieldElement::value) in decimal.
+///
+/// ```
+/// # use tip5_hash::BFieldElement;
+/// assert_eq!("42", format!("{}", BFieldElement::new(42)));
+/// ```
+impl fmt::Display for BFieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value(), f)
+    }
+}
+
+/// Formats the canonical [value](BFieldElement::value), honoring all formatter flags.
+impl fmt::LowerHex for BFieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.value(), f)
+    }
+}
+
+/// Formats the canonical [value](BFieldElement::value), honoring all formatter flags.
+impl fmt::UpperHex for BFieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.value(), f)
+    }
+}
+
+/// Formats the canonical [value](BFieldElement::value), honoring all formatter flags.
+impl fmt::Binary for BFieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Binary::fmt(&self.value(), f)
+    }
+}
+
+/// The error returned when converting an integer that is not smaller than [`BFieldElement::P`]
+/// into a [`BFieldElement`] without reduction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotCanonicalError(pub u64);
+
+impl fmt::Display for NotCanonicalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "value {} is not canonical, must be smaller than {}",
+            self.0,
+            BFieldElement::P
+        )
+    }
+}
+
+impl Error for NotCanonicalError {}
+
+/// The error returned when the canonical value of a [`BFieldElement`] does not fit into the
+/// requested integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromBFieldElementError {
+    /// The canonical value of the field element.
+    pub value: u64,
+
+    /// The name of the requested integer type.
+    pub target: &'static str,
+}
+
+impl fmt::Display for TryFromBFieldElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "field element {} does not fit into {}",
+            self.value, self.target
+        )
+    }
+}
+
+impl Error for TryFromBFieldElementError {}
+
+/// The error returned when converting between a [`BFieldElement`] and its bits fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitDecompositionError {
+    /// More than 64 bits were given.
+    TooManyBits(usize),
+
+    /// The bits encode an integer that is not smaller than [`BFieldElement::P`].
+    NotCanonical(u64),
+
+    /// The canonical value does not fit into the requested number of bits.
+    ValueTooLarge { value: u64, num_bits: usize },
+}
+
+impl fmt::Display for BitDecompositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyBits(num_bits) => {
+                write!(f, "{num_bits} bits do not fit into a field element")
+            }
+            Self::NotCanonical(value) => write!(
+                f,
+                "bits encode {value}, which is not smaller than {}",
+                BFieldElement::P
+            ),
+            Self::ValueTooLarge { value, num_bits } => {
+                write!(f, "field element {value} does not fit into {num_bits} bits")
+            }
+        }
+    }
+}
+
+impl Error for BitDecompositionError {}
+
+/// The error returned when parsing a [`BFieldElement`] from a string fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseBFieldElementError {
+    /// The string is empty or contains characters that are not digits of the expected radix.
+    InvalidCharacters,
+
+    /// The string encodes an integer that does not fit into a `u64`.
+    Overflow,
+
+    /// The string encodes an integer that is not smaller than [`BFieldElement::P`].
+    NotCanonical(u64),
+}
+
+impl fmt::Display for ParseBFieldElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCharacters => write!(f, "invalid characters in field element"),
+            Self::Overflow => write!(f, "field element does not fit into a u64"),
+            Self::NotCanonical(value) => write!(
+                f,
+                "field element {value} is not canonical, must be smaller than {}",
+                BFieldElement::P
+            ),
+        }
     }
-}
\ No newline at end of file
+}
+
+impl Error for ParseBFieldElementError {}
+
+/// Parses a canonical value, either in decimal or, with prefix `0x`, in hexadecimal.
+///
+/// Unlike [`BFieldElement::new`], values that are not smaller than [`BFieldElement::P`] are
+/// rejected rather than reduced, so that parsing round-trips exactly with [`fmt::Display`].
+///
+/// ```
+/// # use tip5_hash::BFieldElement;
+/// assert_eq!(Ok(BFieldElement::new(42)), "42".parse());
+/// assert_eq!(Ok(BFieldElement::new(42)), "0x2a".parse());
+/// assert!("18446744069414584321".parse::<BFieldElement>().is_err());
+/// ```
+impl FromStr for BFieldElement {
+    type Err = ParseBFieldElementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_result = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => s.parse::<u64>(),
+        };
+
+        let value = parse_result.map_err(|err| match err.kind() {
+            IntErrorKind::PosOverflow => ParseBFieldElementError::Overflow,
+            _ => ParseBFieldElementError::InvalidCharacters,
+        })?;
+
+        if value >= Self::P {
+            return Err(ParseBFieldElementError::NotCanonical(value));
+        }
+
+        Ok(Self::new(value))
+    }
+}
+
+/// Elements are ordered by their canonical [value](BFieldElement::value), not by their internal
+/// Montgomery representation.
+///
+/// Every comparison performs a Montgomery reduction of both operands. When sorting large
+/// collections, consider [`slice::sort_by_cached_key`] with [`BFieldElement::value`] instead.
+impl Ord for BFieldElement {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value().cmp(&other.value())
+    }
+}
+
+impl PartialOrd for BFieldElement {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Pow<u8> for BFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn pow(self, exponent: u8) -> Self {
+        self.mod_pow_u32(exponent.into())
+    }
+}
+
+impl Pow<u8> for &BFieldElement {
+    type Output = BFieldElement;
+
+    #[inline]
+    fn pow(self, exponent: u8) -> BFieldElement {
+        self.mod_pow_u32(exponent.into())
+    }
+}
+
+impl Pow<u32> for BFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn pow(self, exponent: u32) -> Self {
+        self.mod_pow_u32(exponent)
+    }
+}
+
+impl Pow<u32> for &BFieldElement {
+    type Output = BFieldElement;
+
+    #[inline]
+    fn pow(self, exponent: u32) -> BFieldElement {
+        self.mod_pow_u32(exponent)
+    }
+}
+
+impl Pow<u64> for BFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn pow(self, exponent: u64) -> Self {
+        self.mod_pow(exponent)
+    }
+}
+
+impl Pow<u64> for &BFieldElement {
+    type Output = BFieldElement;
+
+    #[inline]
+    fn pow(self, exponent: u64) -> BFieldElement {
+        self.mod_pow(exponent)
+    }
+}
+
+impl Add for BFieldElement {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        self.const_add(rhs)
+    }
+}
+
+/// ```
+/// # use tip5_hash::BFieldElement;
+/// let elements = [1, 2, 3, 4].map(BFieldElement::new);
+/// assert_eq!(BFieldElement::new(10), elements.iter().sum());
+/// assert_eq!(BFieldElement::new(10), elements.into_iter().sum());
+/// ```
+impl Sum for BFieldElement {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a BFieldElement> for BFieldElement {
+    #[inline]
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, &x| acc + x)
+    }
+}
+
+/// ```
+/// # use tip5_hash::BFieldElement;
+/// let factorial: BFieldElement = (1..=5).map(BFieldElement::new).product();
+/// assert_eq!(BFieldElement::new(120), factorial);
+/// ```
+impl Product for BFieldElement {
+    #[inline]
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a BFieldElement> for BFieldElement {
+    #[inline]
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, &x| acc * x)
+    }
+}
+
+impl AddAssign for BFieldElement {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl SubAssign for BFieldElement {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs
+    }
+}
+
+impl MulAssign for BFieldElement {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Mul for BFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        self.const_mul(rhs)
+    }
+}
+
+impl Neg for BFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        self.const_neg()
+    }
+}
+
+impl Sub for BFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        self.const_sub(rhs)
+    }
+}
+
+/// # Panics
+///
+/// Panics if the divisor is zero. Use [`BFieldElement::checked_div`] for divisors that might be
+/// zero.
+impl Div for BFieldElement {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        other.inverse() * self
+    }
+}
+
+/// Implement a binary operator for all combinations of owned and borrowed operands by
+/// delegating to the implementation for owned operands.
+macro_rules! impl_binary_op_for_references {
+    ($($op:ident::$method:ident),+ $(,)?) => {$(
+        impl $op<&BFieldElement> for BFieldElement {
+            type Output = BFieldElement;
+
+            #[inline]
+            fn $method(self, rhs: &BFieldElement) -> BFieldElement {
+                $op::$method(self, *rhs)
+            }
+        }
+
+        impl $op<BFieldElement> for &BFieldElement {
+            type Output = BFieldElement;
+
+            #[inline]
+            fn $method(self, rhs: BFieldElement) -> BFieldElement {
+                $op::$method(*self, rhs)
+            }
+        }
+
+        impl $op<&BFieldElement> for &BFieldElement {
+            type Output = BFieldElement;
+
+            #[inline]
+            fn $method(self, rhs: &BFieldElement) -> BFieldElement {
+                $op::$method(*self, *rhs)
+            }
+        }
+    )+};
+}
+
+impl_binary_op_for_references!(Add::add, Sub::sub, Mul::mul, Div::div);
+
+/// Implement a compound assignment operator with a borrowed right-hand side by delegating to
+/// the implementation for an owned right-hand side.
+macro_rules! impl_assign_op_for_references {
+    ($($op:ident::$method:ident),+ $(,)?) => {$(
+        impl $op<&BFieldElement> for BFieldElement {
+            #[inline]
+            fn $method(&mut self, rhs: &BFieldElement) {
+                $op::$method(self, *rhs)
+            }
+        }
+    )+};
+}
+
+impl_assign_op_for_references!(
+    AddAssign::add_assign,
+    SubAssign::sub_assign,
+    MulAssign::mul_assign,
+);
+
+impl Neg for &BFieldElement {
+    type Output = BFieldElement;
+
+    #[inline]
+    fn neg(self) -> BFieldElement {
+        -*self
+    }
+}
+
+/// Samples uniformly from the field by rejection sampling on `u64`s: values not smaller than
+/// [`BFieldElement::P`] are discarded, which happens with probability less than 2^-32.
+///
+/// Through `rand`'s generic implementations, arrays of field elements can be sampled as well,
+/// _e.g._, `rng.gen::<[BFieldElement; 10]>()`.
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<BFieldElement> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> BFieldElement {
+        loop {
+            if let Ok(element) = BFieldElement::try_from(rng.next_u64()) {
+                return element;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "rand")]
+impl BFieldElement {
+    /// Sample `n` field elements uniformly at random. See
+    /// [`fill_random`](Self::fill_random) for details.
+    pub fn random_elements<R: rand::RngCore + ?Sized>(rng: &mut R, n: usize) -> Vec<Self> {
+        let mut elements = vec![Self::ZERO; n];
+        Self::fill_random(rng, &mut elements);
+        elements
+    }
+
+    /// Fill `elements` with field elements sampled uniformly at random.
+    ///
+    /// Random 64-bit words are drawn in bulk. Words that are not smaller than [`P`](Self::P)
+    /// are discarded; every accepted word is uniformly distributed in [0, P) because
+    /// conditioning a uniform distribution on [0, 2^64) to the subset [0, P) yields the
+    /// uniform distribution on that subset. Rejection happens with probability less than
+    /// 2^-32 per word.
+    ///
+    /// The result agrees with sampling each element individually through the
+    /// [`Standard`](rand::distributions::Standard) distribution only if the RNG starts in the
+    /// same state and filling a buffer of `u64`s draws the same words as repeated calls to
+    /// [`next_u64`](rand::RngCore::next_u64). Then, both reject exactly the same words.
+    pub fn fill_random<R: rand::RngCore + ?Sized>(rng: &mut R, elements: &mut [Self]) {
+        const BUFFER_LEN: usize = 64;
+        let mut buffer = [0_u64; BUFFER_LEN];
+
+        let mut num_filled = 0;
+        while num_filled < elements.len() {
+            let num_missing = (elements.len() - num_filled).min(BUFFER_LEN);
+            let words = &mut buffer[..num_missing];
+            rand::Rng::fill(rng, words);
+
+            for &word in words.iter() {
+                if let Ok(element) = Self::try_from(word) {
+                    elements[num_filled] = element;
+                    num_filled += 1;
+                }
+            }
+        }
+    }
+}
+
+// SAFETY: `BFieldElement` is a `repr(transparent)` wrapper around a `u64`. The all-zero bit
+// pattern is the Montgomery representation of zero.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for BFieldElement {}
+
+// SAFETY: `BFieldElement` is a `repr(transparent)` wrapper around a `u64` and thus has no
+// padding. Every bit pattern is memory-safe; words that are not
+// [canonical](BFieldElement::is_canonical_raw) only lead to incorrect arithmetic.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for BFieldElement {}
+
+#[cfg(feature = "bytemuck")]
+impl BFieldElement {
+    /// View the given elements as bytes, without copying.
+    ///
+    /// The bytes are the raw Montgomery representation in native byte order, not the
+    /// canonical values. They are only meaningful to [`try_from_bytes`](Self::try_from_bytes)
+    /// on a machine with the same endianness.
+    pub fn as_bytes(elements: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(elements)
+    }
+
+    /// View the given bytes as field elements, without copying.
+    ///
+    /// The bytes are interpreted as the raw Montgomery representation in native byte order,
+    /// as produced by [`as_bytes`](Self::as_bytes). Fails if `bytes` is not aligned to 8
+    /// bytes or its length is not a multiple of 8.
+    ///
+    /// The same invariant as for [`from_raw_u64`](Self::from_raw_u64) applies to every
+    /// word: bytes of unknown origin should be checked with
+    /// [`is_canonical_raw`](Self::is_canonical_raw).
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<&[Self], bytemuck::PodCastError> {
+        bytemuck::try_cast_slice(bytes)
+    }
+}
+
+/// Uniformly distributed field elements, shrinking toward [zero](BFieldElement::ZERO).
+///
+/// For inputs that exercise edge cases of the arithmetic, see
+/// [`bfield_element_corner_cases`].
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for BFieldElement {
+    type Parameters = ();
+    type Strategy = proptest::strategy::Map<core::ops::Range<u64>, fn(u64) -> Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+
+        (0..Self::P).prop_map(Self::new)
+    }
+}
+
+/// A [`proptest`] strategy biased toward field elements that commonly trigger arithmetic
+/// bugs: 0, 1, P-1, values around 2^32 and P/2, and elements whose internal Montgomery
+/// representation lies close to 0 or P. Uniformly sampled elements are mixed in as well.
+#[cfg(feature = "proptest")]
+pub fn bfield_element_corner_cases() -> impl proptest::strategy::Strategy<Value = BFieldElement> {
+    use proptest::prelude::*;
+
+    const P: u64 = BFieldElement::P;
+    const MAX_OFFSET: u64 = 16;
+
+    let special_values = prop::sample::select(vec![0, 1, 2, P - 2, P - 1, 1 << 32, P / 2]);
+    let around_two_to_the_32 = (1 << 32) - MAX_OFFSET..(1 << 32) + MAX_OFFSET;
+    let around_half_p = P / 2 - MAX_OFFSET..P / 2 + MAX_OFFSET;
+    let just_below_p = P - MAX_OFFSET..P;
+    let near_canonical_values = prop_oneof![
+        special_values,
+        around_two_to_the_32,
+        around_half_p,
+        just_below_p,
+    ]
+    .prop_map(BFieldElement::new);
+
+    let small_raw = (0..MAX_OFFSET).prop_map(BFieldElement::from_raw_u64);
+    let large_raw = (P - MAX_OFFSET..P).prop_map(BFieldElement::from_raw_u64);
+    let near_montgomery_boundary = prop_oneof![small_raw, large_raw];
+
+    prop_oneof![
+        4 => near_canonical_values,
+        2 => near_montgomery_boundary,
+        1 => any::<BFieldElement>(),
+    ]
+}
+
+/// Uniformly distributed field elements. Shrinking happens via the canonical value.
+///
+/// The canonical value is not sampled with `u64::arbitrary`, which favors 0, 1, and
+/// `u64::MAX`, but from uniformly chosen bytes. Values that are not smaller than
+/// [`P`](BFieldElement::P) are rejected.
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for BFieldElement {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        const ALL_BYTES: [u8; 256] = {
+            let mut bytes = [0; 256];
+            let mut i = 0;
+            while i < bytes.len() {
+                bytes[i] = i as u8;
+                i += 1;
+            }
+            bytes
+        };
+
+        loop {
+            let bytes = std::array::from_fn(|_| *g.choose(&ALL_BYTES).unwrap());
+            if let Ok(element) = Self::try_from(u64::from_le_bytes(bytes)) {
+                return element;
+            }
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.value().shrink().map(Self::new))
+    }
+}
+
+/// Serializes the canonical [value](BFieldElement::value), not the internal Montgomery
+/// representation.
+///
+/// Human-readable formats like JSON get a decimal string, since many consumers of such
+/// formats, JavaScript in particular, silently lose precision on integers above 2^53.
+/// Binary formats get the compact u64.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BFieldElement {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u64(self.value())
+        }
+    }
+}
+
+/// Deserializes a canonical value. Values that are not smaller than [`BFieldElement::P`] are
+/// rejected rather than reduced.
+///
+/// Human-readable formats accept both a string, parsed like with [`FromStr`], and an
+/// unsigned integer.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BFieldElement {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BFieldElementVisitor)
+        } else {
+            let value = u64::deserialize(deserializer)?;
+            Self::try_from(value).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct BFieldElementVisitor;
+
+#[cfg(feature = "serde")]
+impl serde::de::Visitor<'_> for BFieldElementVisitor {
+    type Value = BFieldElement;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a canonical field element as a string or an unsigned integer"
+        )
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        BFieldElement::try_from(value).map_err(E::custom)
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        let Ok(value) = u64::try_from(value) else {
+            return Err(E::invalid_value(
+                serde::de::Unexpected::Signed(value),
+                &self,
+            ));
+        };
+        self.visit_u64(value)
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+/// Serializes the canonical [value](BFieldElement::value) as a little-endian u64.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for BFieldElement {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.value().serialize(writer)
+    }
+}
+
+/// Deserializes a canonical value. Values that are not smaller than [`BFieldElement::P`] are
+/// rejected rather than reduced.
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for BFieldElement {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        let value = u64::deserialize_reader(reader)?;
+        Self::try_from(value)
+            .map_err(|err| borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// The [archived](rkyv::Archive) form of a [`BFieldElement`]: its canonical
+/// [value](BFieldElement::value), not the internal Montgomery representation.
+///
+/// [Validation](rkyv::bytecheck::CheckBytes) rejects values that are not smaller than
+/// [`BFieldElement::P`], so archives from untrusted sources can be accessed safely.
+#[cfg(feature = "rkyv")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ArchivedBFieldElement(rkyv::Archived<u64>);
+
+// `Archived<u64>` is `u64` unless rkyv is configured with a fixed endianness, in which case
+// the conversions are not the identity.
+#[cfg(feature = "rkyv")]
+#[allow(clippy::useless_conversion)]
+impl ArchivedBFieldElement {
+    pub(crate) fn new(element: BFieldElement) -> Self {
+        Self(rkyv::Archived::<u64>::from(element.value()))
+    }
+
+    /// The canonical value of the archived element.
+    pub fn value(&self) -> u64 {
+        u64::from(self.0)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl From<ArchivedBFieldElement> for BFieldElement {
+    fn from(archived: ArchivedBFieldElement) -> Self {
+        Self::new(archived.value())
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl PartialEq<BFieldElement> for ArchivedBFieldElement {
+    fn eq(&self, other: &BFieldElement) -> bool {
+        self.value() == other.value()
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl rkyv::Archive for BFieldElement {
+    type Archived = ArchivedBFieldElement;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
+        // SAFETY: the caller guarantees that `out` is valid for writes and properly aligned
+        unsafe { out.write(ArchivedBFieldElement::new(*self)) };
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<S: rkyv::Fallible + ?Sized> rkyv::Serialize<S> for BFieldElement {
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<D: rkyv::Fallible + ?Sized> rkyv::Deserialize<BFieldElement, D> for ArchivedBFieldElement {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<BFieldElement, D::Error> {
+        Ok(BFieldElement::from(*self))
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<C: ?Sized> rkyv::bytecheck::CheckBytes<C> for ArchivedBFieldElement {
+    type Error = NotCanonicalError;
+
+    unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
+        // SAFETY: the caller guarantees that `value` is aligned and points to enough bytes.
+        // Every bit pattern is a valid `Archived<u64>`.
+        let archived = unsafe { &*value };
+        BFieldElement::try_from(archived.value())?;
+        Ok(archived)
+    }
+}
+
+/// Compares the internal representations, which are unique, without branching on the
+/// position of differing bits.
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for BFieldElement {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl subtle::ConditionallySelectable for BFieldElement {
+    #[inline]
+    fn conditional_select(a: &Self, b: &Self, choice: subtle::Choice) -> Self {
+        Self(u64::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+/// The all-zero word is the Montgomery representation of [`BFieldElement::ZERO`], so the
+/// [`Default`] value is a proper zeroization target. This implies [`zeroize::Zeroize`] for
+/// `BFieldElement` as well as for arrays and slices of it, all of which use volatile writes.
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for BFieldElement {}
+
+/// A [`BFieldElement`] holding secret material, for example a key. The element is
+/// [zeroized](zeroize::Zeroize) when the wrapper is dropped, and its [`Debug`](fmt::Debug)
+/// representation never reveals the value.
+///
+/// There is no [`PartialEq`] because `==` may branch on the secret. With feature `subtle`,
+/// secret elements are compared in constant time with
+/// [`ConstantTimeEq`](subtle::ConstantTimeEq).
+#[cfg(feature = "zeroize")]
+#[derive(Clone, Default)]
+pub struct SecretBFieldElement(BFieldElement);
+
+#[cfg(feature = "zeroize")]
+impl SecretBFieldElement {
+    pub const fn new(element: BFieldElement) -> Self {
+        Self(element)
+    }
+
+    /// The secret element. Copies of it are not zeroized automatically.
+    pub const fn expose_secret(&self) -> &BFieldElement {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl From<BFieldElement> for SecretBFieldElement {
+    fn from(element: BFieldElement) -> Self {
+        Self(element)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl fmt::Debug for SecretBFieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretBFieldElement(<redacted>)")
+    }
+}
+
+#[cfg(all(feature = "zeroize", feature = "subtle"))]
+impl subtle::ConstantTimeEq for SecretBFieldElement {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretBFieldElement {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretBFieldElement {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretBFieldElement {}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_montgomery_reduction() {
+        let input = 2_609_026_890_597_981_882u128;
+        let expected = 11_259_563_268_822_605_859u64;
+
+        let result = BFieldElement::montyred(input);
+
+        assert_eq!(result, expected, "Montgomery reduction failed");
+    }
+
+    #[test]
+    fn test_montify() {
+        let value = 12_045_832_659_793_544_965;
+        let bfe = BFieldElement::new(value);
+        let expected = 9_712_864_734_344_745_984u64;
+
+        let result = BFieldElement::new(value).0;
+
+        assert_eq!(result, expected, "Montification failed");
+
+        let red = bfe.value();
+        assert_eq!(red, value, "Canonical representation failed");
+    }
+
+    #[test]
+    fn mod_pow_of_zero_to_the_zero_is_one() {
+        assert_eq!(BFieldElement::ONE, BFieldElement::ZERO.mod_pow(0));
+        assert_eq!(BFieldElement::ONE, BFieldElement::ZERO.mod_pow_u32(0));
+        assert_eq!(BFieldElement::ZERO, BFieldElement::ZERO.mod_pow(1));
+    }
+
+    proptest! {
+        #[test]
+        fn mod_pow_edge_exponents(value: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(BFieldElement::ONE, x.mod_pow(0));
+            prop_assert_eq!(x, x.mod_pow(1));
+            prop_assert_eq!(x * x, x.mod_pow(2));
+        }
+
+        #[test]
+        fn mod_pow_agrees_with_repeated_multiplication(value: u64, exponent in 0_u32..300) {
+            let x = BFieldElement::new(value);
+            let mut expected = BFieldElement::ONE;
+            for _ in 0..exponent {
+                expected *= x;
+            }
+
+            prop_assert_eq!(expected, x.mod_pow(exponent.into()));
+            prop_assert_eq!(expected, x.mod_pow_u32(exponent));
+        }
+
+        #[test]
+        fn mod_pow_u32_agrees_with_mod_pow(value: u64, exponent: u32) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(x.mod_pow(exponent.into()), x.mod_pow_u32(exponent));
+        }
+
+        #[test]
+        fn mod_pow_by_p_minus_two_is_inverse(value in 1..BFieldElement::P) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(x.inverse(), x.mod_pow(BFieldElement::P - 2));
+        }
+    }
+
+    #[test]
+    fn pow_trait_edge_exponents() {
+        let x = BFieldElement::new(0xdead_beef_cafe_babe);
+
+        assert_eq!(BFieldElement::ONE, x.pow(0_u8));
+        assert_eq!(BFieldElement::ONE, x.pow(0_u32));
+        assert_eq!(BFieldElement::ONE, x.pow(0_u64));
+
+        assert_eq!(x, x.pow(1_u8));
+        assert_eq!(x, x.pow(1_u32));
+        assert_eq!(x, x.pow(1_u64));
+
+        assert_eq!(x * x, x.pow(2_u8));
+        assert_eq!(x * x, x.pow(2_u32));
+        assert_eq!(x * x, x.pow(2_u64));
+
+        // x^(P-1) = 1 for all non-zero x, so u64::MAX = (P-1) + (2^32 - 1) reduces accordingly.
+        let exponent_offset = u64::MAX - (BFieldElement::P - 1);
+        assert_eq!(BFieldElement::ONE, x.pow(BFieldElement::P - 1));
+        assert_eq!(x, x.pow(BFieldElement::P));
+        assert_eq!(x.pow(exponent_offset), x.pow(u64::MAX));
+        assert_eq!(x.pow(exponent_offset - 1), x.pow(u64::MAX - 1));
+        assert_eq!(x.pow(u64::from(u32::MAX)), x.pow(u32::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn pow_trait_impls_agree(value: u64, exponent: u8) {
+            let x = BFieldElement::new(value);
+            let by_u8 = x.pow(exponent);
+            prop_assert_eq!(by_u8, (&x).pow(exponent));
+            prop_assert_eq!(by_u8, x.pow(u32::from(exponent)));
+            prop_assert_eq!(by_u8, (&x).pow(u32::from(exponent)));
+            prop_assert_eq!(by_u8, x.pow(u64::from(exponent)));
+            prop_assert_eq!(by_u8, (&x).pow(u64::from(exponent)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to find the multiplicative inverse of zero.")]
+    fn inv_of_zero_panics() {
+        let _ = BFieldElement::ZERO.inv();
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to find the multiplicative inverse of zero.")]
+    fn inv_of_zero_reference_panics() {
+        let _ = (&BFieldElement::ZERO).inv();
+    }
+
+    proptest! {
+        #[test]
+        fn inv_is_multiplicative_inverse(value in 1..BFieldElement::P) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(BFieldElement::ONE, x.inv() * x);
+            prop_assert_eq!(x.inverse(), (&x).inv());
+        }
+    }
+
+    #[test]
+    fn batch_inversion_of_empty_slice_is_a_no_op() {
+        let mut elements = [];
+        BFieldElement::batch_inversion(&mut elements);
+        assert!(BFieldElement::batch_inverse(&elements).is_empty());
+    }
+
+    #[test]
+    fn batch_inversion_maps_zero_to_zero() {
+        let elements = [0, 1, 0, 2, BFieldElement::MAX, 0].map(BFieldElement::new);
+        let inverses = BFieldElement::batch_inverse(&elements);
+
+        for (element, inverse) in elements.into_iter().zip(inverses) {
+            if element.is_zero() {
+                assert!(inverse.is_zero());
+            } else {
+                assert_eq!(element.inverse(), inverse);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn batch_inversion_agrees_with_inverse(values: Vec<u64>) {
+            let elements = values.into_iter().map(BFieldElement::new).collect::<Vec<_>>();
+            let inverses = BFieldElement::batch_inverse(&elements);
+            prop_assert_eq!(elements.len(), inverses.len());
+
+            let mut inverted_in_place = elements.clone();
+            BFieldElement::batch_inversion(&mut inverted_in_place);
+            prop_assert_eq!(&inverses, &inverted_in_place);
+
+            for (element, inverse) in elements.into_iter().zip(inverses) {
+                let expected = if element.is_zero() { element } else { element.inverse() };
+                prop_assert_eq!(expected, inverse);
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_or_zero_edge_cases() {
+        assert_eq!(BFieldElement::ZERO, BFieldElement::ZERO.inverse_or_zero());
+        assert_eq!(BFieldElement::ONE, BFieldElement::ONE.inverse_or_zero());
+
+        let minus_one = BFieldElement::new(BFieldElement::MAX);
+        assert_eq!(minus_one, minus_one.inverse_or_zero());
+        assert_eq!(minus_one.inverse(), minus_one.inverse_or_zero());
+    }
+
+    proptest! {
+        #[test]
+        fn inverse_or_zero_agrees_with_inverse(value in 1..BFieldElement::P) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(x.inverse(), x.inverse_or_zero());
+            prop_assert_eq!(BFieldElement::ONE, x * x.inverse_or_zero());
+        }
+    }
+
+    #[test]
+    fn try_inverse_of_zero_is_none() {
+        assert_eq!(None, BFieldElement::ZERO.try_inverse());
+    }
+
+    #[test]
+    fn checked_div_by_zero_is_none() {
+        let x = BFieldElement::new(42);
+        assert_eq!(None, x.checked_div(BFieldElement::ZERO));
+        assert_eq!(None, BFieldElement::ZERO.checked_div(BFieldElement::ZERO));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to find the multiplicative inverse of zero.")]
+    fn div_by_zero_panics() {
+        let _ = BFieldElement::ONE / BFieldElement::ZERO;
+    }
+
+    proptest! {
+        #[test]
+        fn try_inverse_agrees_with_inverse(value in 1..BFieldElement::P) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(Some(x.inverse()), x.try_inverse());
+        }
+
+        #[test]
+        fn checked_div_agrees_with_div_operator(dividend: u64, divisor in 1..BFieldElement::P) {
+            let dividend = BFieldElement::new(dividend);
+            let divisor = BFieldElement::new(divisor);
+            let quotient = dividend / divisor;
+
+            prop_assert_eq!(Some(quotient), dividend.checked_div(divisor));
+            prop_assert_eq!(dividend, quotient * divisor);
+        }
+    }
+
+    #[test]
+    fn primitive_roots_of_unity_have_exact_order() {
+        for log_2_of_order in 0..=32 {
+            let order = 1_u64 << log_2_of_order;
+            let root = BFieldElement::primitive_root_of_unity(order).unwrap();
+            assert_eq!(
+                BFieldElement::ONE,
+                root.mod_pow(order),
+                "order 2^{log_2_of_order}"
+            );
+            if order > 1 {
+                assert_ne!(BFieldElement::ONE, root.mod_pow(order / 2));
+            }
+        }
+    }
+
+    #[test]
+    fn powers_of_special_elements() {
+        assert_eq!(bfe_vec![1, 0, 0, 0], BFieldElement::powers_of(bfe!(0), 4));
+        assert_eq!(bfe_vec![1, 1, 1], BFieldElement::powers_of(bfe!(1), 3));
+        assert_eq!(
+            bfe_vec![1, -1, 1, -1],
+            BFieldElement::powers_of(bfe!(-1), 4)
+        );
+        assert_eq!(
+            bfe_vec![1, 2, 4, 8, 16],
+            BFieldElement::powers_of(bfe!(2), 5)
+        );
+        assert!(BFieldElement::powers_of(bfe!(2), 0).is_empty());
+        assert!(BFieldElement::coset_domain(bfe!(3), bfe!(2), 0).is_empty());
+    }
+
+    #[test]
+    fn coset_domain_of_root_of_unity_is_closed_under_generator() {
+        let n = 16;
+        let generator = BFieldElement::primitive_root_of_unity(n as u64).unwrap();
+        let offset = BFieldElement::GENERATOR;
+        let domain = BFieldElement::coset_domain(offset, generator, n);
+
+        assert_eq!(offset, domain[n - 1] * generator);
+        let product = domain.iter().copied().product::<BFieldElement>();
+        let expected = offset.mod_pow(n as u64) * generator.mod_pow((n * (n - 1) / 2) as u64);
+        assert_eq!(expected, product);
+    }
+
+    proptest! {
+        #[test]
+        fn powers_agree_with_mod_pow(base: u64, indices in proptest::collection::vec(0_usize..2_000, 1..10)) {
+            let base = BFieldElement::new(base);
+            let powers = BFieldElement::powers_of(base, 2_000);
+            prop_assert_eq!(2_000, powers.len());
+            for i in indices {
+                prop_assert_eq!(base.mod_pow(i as u64), powers[i]);
+                prop_assert_eq!(Some(powers[i]), base.powers().nth(i));
+            }
+        }
+
+        #[test]
+        fn coset_domain_agrees_with_offset_times_powers(offset: u64, generator: u64, n in 0_usize..100) {
+            let offset = BFieldElement::new(offset);
+            let generator = BFieldElement::new(generator);
+            let domain = BFieldElement::coset_domain(offset, generator, n);
+            prop_assert_eq!(n, domain.len());
+            for (i, &point) in domain.iter().enumerate() {
+                prop_assert_eq!(offset * generator.mod_pow(i as u64), point);
+            }
+        }
+    }
+
+    #[test]
+    fn primitive_root_of_unity_of_unsupported_order_is_none() {
+        for order in [
+            0,
+            3,
+            6,
+            12,
+            1 << 33,
+            1 << 63,
+            u64::MAX,
+            BFieldElement::P - 1,
+        ] {
+            assert_eq!(
+                None,
+                BFieldElement::primitive_root_of_unity(order),
+                "{order}"
+            );
+        }
+    }
+
+    #[test]
+    fn primitive_roots_of_unity_are_derived_from_generator() {
+        let generator = BFieldElement::GENERATOR;
+        for log_2_of_order in 0..=32 {
+            let order = 1_u64 << log_2_of_order;
+            let expected = generator.mod_pow((BFieldElement::P - 1) / order);
+            assert_eq!(
+                Some(expected),
+                BFieldElement::primitive_root_of_unity(order)
+            );
+        }
+    }
+
+    /// The distinct prime factors of P-1 = 2^32 · 3 · 5 · 17 · 257 · 65537.
+    const PRIME_FACTORS_OF_P_MINUS_ONE: [u64; 6] = [2, 3, 5, 17, 257, 65537];
+
+    #[test]
+    fn prime_factors_of_p_minus_one_are_complete() {
+        let odd_part: u64 = PRIME_FACTORS_OF_P_MINUS_ONE[1..].iter().product();
+        assert_eq!(BFieldElement::P - 1, odd_part << BFieldElement::TWO_ADICITY);
+    }
+
+    #[test]
+    fn generator_generates_the_multiplicative_group() {
+        let generator = BFieldElement::GENERATOR;
+        assert_eq!(BFieldElement::ONE, generator.mod_pow(BFieldElement::P - 1));
+        for prime_factor in PRIME_FACTORS_OF_P_MINUS_ONE {
+            let exponent = (BFieldElement::P - 1) / prime_factor;
+            assert_ne!(
+                BFieldElement::ONE,
+                generator.mod_pow(exponent),
+                "{prime_factor}"
+            );
+        }
+    }
+
+    #[test]
+    fn two_adic_root_of_unity_has_exact_order() {
+        let root = BFieldElement::TWO_ADIC_ROOT_OF_UNITY;
+        let order = 1_u64 << BFieldElement::TWO_ADICITY;
+        assert_eq!(BFieldElement::ONE, root.mod_pow(order));
+        assert_ne!(BFieldElement::ONE, root.mod_pow(order / 2));
+
+        let odd_part = (BFieldElement::P - 1) >> BFieldElement::TWO_ADICITY;
+        assert_eq!(BFieldElement::GENERATOR.mod_pow(odd_part), root);
+    }
+
+    #[test]
+    fn legendre_symbol_of_zero_is_zero() {
+        assert_eq!(0, BFieldElement::ZERO.legendre_symbol());
+        assert!(BFieldElement::ZERO.is_square());
+    }
+
+    #[test]
+    fn generator_is_not_a_square() {
+        assert_eq!(-1, BFieldElement::GENERATOR.legendre_symbol());
+        assert!(!BFieldElement::GENERATOR.is_square());
+    }
+
+    #[test]
+    fn squares_of_samples_are_squares() {
+        for value in (0..1_000).chain(BFieldElement::MAX - 1_000..=BFieldElement::MAX) {
+            let square = BFieldElement::new(value).square();
+            assert!(square.is_square(), "{value}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn legendre_symbol_is_multiplicative(a: u64, b: u64) {
+            let a = BFieldElement::new(a);
+            let b = BFieldElement::new(b);
+            prop_assert_eq!(a.legendre_symbol() * b.legendre_symbol(), (a * b).legendre_symbol());
+        }
+
+        #[test]
+        fn non_zero_squares_have_legendre_symbol_one(value in 1..BFieldElement::P) {
+            let square = BFieldElement::new(value).square();
+            prop_assert_eq!(1, square.legendre_symbol());
+        }
+    }
+
+    #[test]
+    fn sqrt_of_small_constants() {
+        assert_eq!(Some(BFieldElement::ZERO), BFieldElement::ZERO.sqrt());
+        assert_eq!(Some(BFieldElement::ONE), BFieldElement::ONE.sqrt());
+        assert_eq!(Some(BFieldElement::new(2)), BFieldElement::new(4).sqrt());
+        assert_eq!(Some(BFieldElement::new(3)), BFieldElement::new(9).sqrt());
+        assert_eq!(Some(BFieldElement::new(12)), BFieldElement::new(144).sqrt());
+
+        // -1 is a square since 4 divides P-1; its roots are the primitive 4th roots of unity
+        let minus_one = -BFieldElement::ONE;
+        let root_of_minus_one = minus_one.sqrt().unwrap();
+        assert_eq!(minus_one, root_of_minus_one.square());
+        assert!(root_of_minus_one.value() <= BFieldElement::P / 2);
+    }
+
+    #[test]
+    fn sqrt_of_small_integers_converted_with_from() {
+        for root in [2_u8, 3, 12] {
+            let square = u16::from(root) * u16::from(root);
+            let expected = Some(BFieldElement::from(root));
+            assert_eq!(expected, BFieldElement::from(square).sqrt());
+        }
+    }
+
+    #[test]
+    fn sqrt_of_non_square_is_none() {
+        assert_eq!(None, BFieldElement::GENERATOR.sqrt());
+        assert_eq!(None, BFieldElement::TWO_ADIC_ROOT_OF_UNITY.sqrt());
+    }
+
+    proptest! {
+        #[test]
+        fn sqrt_of_square_is_canonical_root(value: u64) {
+            let x = BFieldElement::new(value);
+            let root = x.square().sqrt().unwrap();
+            prop_assert!(root == x || root == -x);
+            prop_assert!(root.value() <= BFieldElement::P / 2);
+        }
+
+        #[test]
+        fn sqrt_exists_if_and_only_if_element_is_square(value: u64) {
+            let x = BFieldElement::new(value);
+            match x.sqrt() {
+                Some(root) => prop_assert_eq!(x, root.square()),
+                None => prop_assert!(!x.is_square()),
+            }
+        }
+    }
+
+    #[test]
+    fn sum_of_empty_iterator_is_zero() {
+        assert_eq!(
+            BFieldElement::ZERO,
+            std::iter::empty::<BFieldElement>().sum()
+        );
+        assert_eq!(BFieldElement::ZERO, [].iter().sum());
+    }
+
+    proptest! {
+        #[test]
+        fn sum_agrees_with_fold(values in proptest::collection::vec(any::<u64>(), 0..1_000)) {
+            let elements = values.into_iter().map(BFieldElement::new).collect::<Vec<_>>();
+            let expected = elements.iter().fold(BFieldElement::ZERO, |acc, &x| acc + x);
+
+            prop_assert_eq!(expected, elements.iter().sum::<BFieldElement>());
+            prop_assert_eq!(expected, elements.into_iter().sum::<BFieldElement>());
+        }
+    }
+
+    #[test]
+    fn product_of_empty_iterator_is_one() {
+        assert_eq!(
+            BFieldElement::ONE,
+            std::iter::empty::<BFieldElement>().product()
+        );
+        assert_eq!(BFieldElement::ONE, [].iter().product());
+    }
+
+    #[test]
+    fn product_containing_zero_is_zero() {
+        let elements = [3, 1, 4, 0, 5, 9].map(BFieldElement::new);
+        assert_eq!(BFieldElement::ZERO, elements.iter().product());
+        assert_eq!(BFieldElement::ZERO, elements.into_iter().product());
+    }
+
+    proptest! {
+        #[test]
+        fn product_agrees_with_loop(values in proptest::collection::vec(any::<u64>(), 0..1_000)) {
+            let elements = values.into_iter().map(BFieldElement::new).collect::<Vec<_>>();
+            let mut expected = BFieldElement::ONE;
+            for &element in &elements {
+                expected *= element;
+            }
+
+            prop_assert_eq!(expected, elements.iter().product::<BFieldElement>());
+            prop_assert_eq!(expected, elements.into_iter().product::<BFieldElement>());
+        }
+    }
+
+    #[test]
+    fn ordering_around_boundaries() {
+        let zero = BFieldElement::ZERO;
+        let one = BFieldElement::ONE;
+        let max = BFieldElement::new(BFieldElement::MAX);
+
+        assert!(zero < one);
+        assert!(one < max);
+        assert!(zero < max);
+        assert_eq!(
+            Ordering::Equal,
+            max.cmp(&BFieldElement::new(BFieldElement::MAX))
+        );
+
+        // The Montgomery representation of 2^32 is P-1, which exceeds the representation of
+        // P-1. Ordering must follow the canonical values regardless.
+        let two_pow_32 = BFieldElement::new(1 << 32);
+        assert!(two_pow_32.raw_u64() > max.raw_u64());
+        assert!(two_pow_32 < max);
+    }
+
+    proptest! {
+        #[test]
+        fn sorting_agrees_with_sorting_values(mut values: Vec<u64>) {
+            values.extend([0, 1, 2, BFieldElement::MAX - 1, BFieldElement::MAX]);
+            let mut elements = values.iter().copied().map(BFieldElement::new).collect::<Vec<_>>();
+            elements.sort();
+
+            let mut canonical_values = values.into_iter().map(|v| v % BFieldElement::P).collect::<Vec<_>>();
+            canonical_values.sort();
+
+            let sorted_values = elements.into_iter().map(|e| e.value()).collect::<Vec<_>>();
+            prop_assert_eq!(canonical_values, sorted_values);
+        }
+    }
+
+    #[test]
+    fn display_prints_canonical_value() {
+        assert_eq!("0", BFieldElement::ZERO.to_string());
+        assert_eq!("1", BFieldElement::ONE.to_string());
+        assert_eq!(
+            "18446744069414584320",
+            BFieldElement::new(BFieldElement::MAX).to_string()
+        );
+
+        // the Montgomery representation of 1 is 2^32 - 1
+        assert_ne!(BFieldElement::ONE.raw_u64(), BFieldElement::ONE.value());
+        assert_eq!("42", BFieldElement::new(BFieldElement::P + 42).to_string());
+        assert_eq!("  7", format!("{:>3}", BFieldElement::new(7)));
+    }
+
+    #[test]
+    fn parse_decimal_and_hexadecimal() {
+        assert_eq!(Ok(BFieldElement::ZERO), "0".parse());
+        assert_eq!(Ok(BFieldElement::ONE), "0x1".parse());
+        assert_eq!(Ok(BFieldElement::new(0xabcdef)), "0XABCdef".parse());
+        let max = BFieldElement::new(BFieldElement::MAX);
+        assert_eq!(Ok(max), "18446744069414584320".parse());
+        assert_eq!(Ok(max), "0xffffffff00000000".parse());
+    }
+
+    #[test]
+    fn parse_errors() {
+        use ParseBFieldElementError::*;
+
+        for invalid in ["", "0x", "-1", "1.0", "0b101", "12a", " 1", "0xg"] {
+            let err = invalid.parse::<BFieldElement>();
+            assert_eq!(Err(InvalidCharacters), err, "{invalid:?}");
+        }
+
+        for overflowing in ["18446744073709551616", "0x10000000000000000"] {
+            assert_eq!(Err(Overflow), overflowing.parse::<BFieldElement>());
+        }
+
+        let p = BFieldElement::P;
+        assert_eq!(Err(NotCanonical(p)), p.to_string().parse::<BFieldElement>());
+        assert_eq!(
+            Err(NotCanonical(u64::MAX)),
+            "0xffffffffffffffff".parse::<BFieldElement>()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn parse_round_trips_with_display(value: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(Ok(x), x.to_string().parse());
+        }
+
+        #[test]
+        fn parse_does_not_reduce(value in BFieldElement::P..) {
+            let err = value.to_string().parse::<BFieldElement>();
+            prop_assert_eq!(Err(ParseBFieldElementError::NotCanonical(value)), err);
+        }
+    }
+
+    #[test]
+    fn hex_and_binary_formatting() {
+        let x = BFieldElement::new(0xbeef);
+        assert_eq!("beef", format!("{x:x}"));
+        assert_eq!("BEEF", format!("{x:X}"));
+        assert_eq!("0xbeef", format!("{x:#x}"));
+        assert_eq!("0xBEEF", format!("{x:#X}"));
+        assert_eq!("0x000000000000beef", format!("{x:#018x}"));
+        assert_eq!("000000000000BEEF", format!("{x:016X}"));
+        assert_eq!("____beef", format!("{x:_>8x}"));
+
+        let five = BFieldElement::new(5);
+        assert_eq!("101", format!("{five:b}"));
+        assert_eq!("0b101", format!("{five:#b}"));
+        assert_eq!("0b00000101", format!("{five:#010b}"));
+
+        let max = BFieldElement::new(BFieldElement::MAX);
+        assert_eq!("0xffffffff00000000", format!("{max:#x}"));
+
+        // the Montgomery representation of 1 is 0xffffffff
+        assert_eq!("1", format!("{:x}", BFieldElement::ONE));
+        assert_eq!("1", format!("{:b}", BFieldElement::ONE));
+    }
+
+    proptest! {
+        #[test]
+        fn hex_and_binary_formatting_use_canonical_value(value: u64) {
+            let x = BFieldElement::new(value);
+            let canonical = value % BFieldElement::P;
+
+            prop_assert_eq!(format!("{canonical:x}"), format!("{x:x}"));
+            prop_assert_eq!(format!("{canonical:X}"), format!("{x:X}"));
+            prop_assert_eq!(format!("{canonical:b}"), format!("{x:b}"));
+        }
+    }
+
+    #[test]
+    fn from_bool() {
+        assert_eq!(BFieldElement::ONE, BFieldElement::from(true));
+        assert_eq!(BFieldElement::ZERO, BFieldElement::from(false));
+    }
+
+    #[test]
+    fn const_conversions_agree_with_from() {
+        const TRUE: BFieldElement = BFieldElement::from_bool(true);
+        const U32_MAX: BFieldElement = BFieldElement::from_u32(u32::MAX);
+        assert_eq!(BFieldElement::from(true), TRUE);
+        assert_eq!(BFieldElement::from(false), BFieldElement::from_bool(false));
+        assert_eq!(BFieldElement::from(u32::MAX), U32_MAX);
+    }
+
+    #[test]
+    fn from_small_unsigned_integers() {
+        assert_eq!(BFieldElement::new(255), BFieldElement::from(u8::MAX));
+        assert_eq!(BFieldElement::new(65_535), BFieldElement::from(u16::MAX));
+        assert_eq!(
+            BFieldElement::new(4_294_967_295),
+            BFieldElement::from(u32::MAX)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn from_u32_agrees_with_new(value: u32) {
+            let x = BFieldElement::from(value);
+            prop_assert_eq!(BFieldElement::new(value.into()), x);
+            prop_assert_eq!(u64::from(value), x.value());
+        }
+
+        #[test]
+        fn from_u16_and_u8_agree_with_new(value: u16) {
+            prop_assert_eq!(BFieldElement::new(value.into()), BFieldElement::from(value));
+            let byte = value as u8;
+            prop_assert_eq!(BFieldElement::new(byte.into()), BFieldElement::from(byte));
+        }
+    }
+
+    #[test]
+    fn from_negative_signed_integers() {
+        assert_eq!(-BFieldElement::ONE, BFieldElement::from(-1_i64));
+        assert_eq!(-BFieldElement::ONE, BFieldElement::from(-1_i32));
+        assert_eq!(BFieldElement::P - 2, BFieldElement::from(-2_i64).value());
+        assert_eq!(BFieldElement::ZERO, BFieldElement::from(0_i64));
+
+        let i64_min = BFieldElement::from(i64::MIN);
+        assert_eq!(BFieldElement::P - (1 << 63), i64_min.value());
+        assert_eq!(BFieldElement::ZERO, i64_min + BFieldElement::new(1 << 63));
+
+        let i32_min = BFieldElement::from(i32::MIN);
+        assert_eq!(BFieldElement::P - (1 << 31), i32_min.value());
+    }
+
+    proptest! {
+        #[test]
+        fn from_i64_is_additive_inverse_of_negation(value in (i64::MIN + 1)..) {
+            let x = BFieldElement::from(value);
+            prop_assert_eq!(BFieldElement::ZERO, x + BFieldElement::from(-value));
+        }
+
+        #[test]
+        fn from_non_negative_i64_agrees_with_new(value in 0_i64..) {
+            prop_assert_eq!(BFieldElement::new(value as u64), BFieldElement::from(value));
+        }
+
+        #[test]
+        fn from_i32_agrees_with_from_i64(value: i32) {
+            prop_assert_eq!(BFieldElement::from(i64::from(value)), BFieldElement::from(value));
+        }
+    }
+
+    #[test]
+    fn try_from_u64_boundaries() {
+        let max = BFieldElement::MAX;
+        assert_eq!(Ok(BFieldElement::new(max)), BFieldElement::try_from(max));
+
+        let p = BFieldElement::P;
+        assert_eq!(Err(NotCanonicalError(p)), BFieldElement::try_from(p));
+        assert_eq!(
+            Err(NotCanonicalError(u64::MAX)),
+            BFieldElement::try_from(u64::MAX)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn try_from_canonical_u64_agrees_with_new(value in 0..BFieldElement::P) {
+            prop_assert_eq!(Ok(BFieldElement::new(value)), BFieldElement::try_from(value));
+        }
+
+        #[test]
+        fn try_from_non_canonical_u64_fails(value in BFieldElement::P..) {
+            prop_assert_eq!(Err(NotCanonicalError(value)), BFieldElement::try_from(value));
+        }
+    }
+
+    #[test]
+    fn try_from_usize_boundaries() {
+        assert_eq!(Ok(BFieldElement::ZERO), BFieldElement::try_from(0_usize));
+        assert_eq!(
+            Ok(BFieldElement::from(u32::MAX)),
+            BFieldElement::try_from(u32::MAX as usize)
+        );
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let max = BFieldElement::MAX as usize;
+            assert_eq!(
+                Ok(BFieldElement::new(max as u64)),
+                BFieldElement::try_from(max)
+            );
+
+            let p = BFieldElement::P as usize;
+            let err = NotCanonicalError(BFieldElement::P);
+            assert_eq!(Err(err), BFieldElement::try_from(p));
+            assert_eq!(
+                Err(NotCanonicalError(u64::MAX)),
+                BFieldElement::try_from(usize::MAX)
+            );
+        }
+    }
+
+    #[test]
+    fn try_from_usize_is_usable_in_generic_code() {
+        fn convert_indices<T: TryFrom<usize>>(indices: &[usize]) -> Vec<T> {
+            indices
+                .iter()
+                .filter_map(|&i| T::try_from(i).ok())
+                .collect()
+        }
+
+        let elements = convert_indices::<BFieldElement>(&[0, 1, 2]);
+        assert_eq!(
+            vec![0_u32, 1, 2]
+                .into_iter()
+                .map(BFieldElement::from)
+                .collect::<Vec<_>>(),
+            elements
+        );
+    }
+
+    #[test]
+    fn into_integers_exact_fit() {
+        let max = BFieldElement::new(BFieldElement::MAX);
+        assert_eq!(BFieldElement::MAX, u64::from(max));
+
+        let x = BFieldElement::from(u8::MAX);
+        assert_eq!(Ok(u8::MAX), u8::try_from(x));
+        assert_eq!(Ok(u32::from(u8::MAX)), u32::try_from(x));
+
+        let x = BFieldElement::from(u32::MAX);
+        assert_eq!(Ok(u32::MAX), u32::try_from(x));
+
+        let x = BFieldElement::new(i64::MAX as u64);
+        assert_eq!(Ok(i64::MAX), i64::try_from(x));
+    }
+
+    #[test]
+    fn into_integers_overflow() {
+        let x = BFieldElement::new(256);
+        let err = TryFromBFieldElementError {
+            value: 256,
+            target: "u8",
+        };
+        assert_eq!(Err(err), u8::try_from(x));
+
+        let x = BFieldElement::new(1 << 32);
+        let err = TryFromBFieldElementError {
+            value: 1 << 32,
+            target: "u32",
+        };
+        assert_eq!(Err(err), u32::try_from(x));
+
+        let x = BFieldElement::new(1 << 63);
+        let err = TryFromBFieldElementError {
+            value: 1 << 63,
+            target: "i64",
+        };
+        assert_eq!(Err(err), i64::try_from(x));
+    }
+
+    #[test]
+    fn into_i64_does_not_recover_negative_integers() {
+        let minus_one = BFieldElement::from(-1_i64);
+        let err = TryFromBFieldElementError {
+            value: BFieldElement::MAX,
+            target: "i64",
+        };
+        assert_eq!(Err(err), i64::try_from(minus_one));
+        assert_eq!(BFieldElement::MAX, u64::from(minus_one));
+
+        let i64_min = BFieldElement::from(i64::MIN);
+        assert_eq!(Ok(i64::MAX - (1 << 32) + 2), i64::try_from(i64_min));
+    }
+
+    proptest! {
+        #[test]
+        fn into_u64_is_canonical_value(value: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(x.value(), u64::from(x));
+        }
+
+        #[test]
+        fn round_trip_through_u32(value: u32) {
+            prop_assert_eq!(Ok(value), u32::try_from(BFieldElement::from(value)));
+        }
+    }
+
+    const A: BFieldElement = BFieldElement::new(0xdead_beef_cafe_babe);
+    const B: BFieldElement = BFieldElement::new(BFieldElement::MAX - 7);
+
+    const _: () = assert!(A.const_add(B).value() == 0xdead_beef_cafe_babe - 8);
+    const _: () = assert!(A.const_sub(A).value() == 0);
+    const _: () = assert!(B.const_neg().value() == 8);
+    const _: () = assert!(BFieldElement::ZERO.const_neg().value() == 0);
+    const _: () = assert!(B.const_mul(B).value() == 64);
+    const _: () = assert!(B.const_add(B.const_neg()).value() == 0);
+
+    #[test]
+    fn const_arithmetic_agrees_with_operators_on_constants() {
+        assert_eq!(A + B, A.const_add(B));
+        assert_eq!(A - B, A.const_sub(B));
+        assert_eq!(A * B, A.const_mul(B));
+        assert_eq!(-A, A.const_neg());
+    }
+
+    proptest! {
+        #[test]
+        fn const_arithmetic_agrees_with_operators(a: u64, b: u64) {
+            let a = BFieldElement::new(a);
+            let b = BFieldElement::new(b);
+
+            prop_assert_eq!(a + b, a.const_add(b));
+            prop_assert_eq!(a - b, a.const_sub(b));
+            prop_assert_eq!(a * b, a.const_mul(b));
+            prop_assert_eq!(-a, a.const_neg());
+        }
+    }
+
+    #[test]
+    fn mul_u32_edge_cases() {
+        let max = BFieldElement::new(BFieldElement::MAX);
+        for x in [BFieldElement::ZERO, BFieldElement::ONE, max] {
+            for k in [0, 1, 2, u32::MAX - 1, u32::MAX] {
+                assert_eq!(x * BFieldElement::from(k), x.mul_u32(k), "{x} · {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn mul_u32_agrees_with_mul_for_mds_constants() {
+        for x in [0, 1, 2, 1 << 32, BFieldElement::MAX].map(BFieldElement::new) {
+            for k in crate::MDS_MATRIX_FIRST_COLUMN {
+                let k = u32::try_from(k).unwrap();
+                assert_eq!(x * BFieldElement::from(k), x.mul_u32(k));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn mul_u32_agrees_with_mul(value: u64, k: u32) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(x * BFieldElement::from(k), x.mul_u32(k));
+        }
+    }
+
+    #[test]
+    fn halve_edge_cases() {
+        let two = BFieldElement::from(2_u8);
+        assert_eq!(BFieldElement::ZERO, BFieldElement::ZERO.halve());
+        assert_eq!(BFieldElement::ONE, two.halve());
+        assert_eq!(two.inverse(), BFieldElement::ONE.halve());
+
+        let max = BFieldElement::new(BFieldElement::MAX);
+        assert_eq!(max, max.halve() * two);
+        assert_eq!(-BFieldElement::ONE, (-two).halve());
+    }
+
+    proptest! {
+        #[test]
+        fn halve_is_inverse_of_doubling(value: u64) {
+            let x = BFieldElement::new(value);
+            let half = x.halve();
+            prop_assert_eq!(x, half * BFieldElement::from(2_u8));
+            prop_assert_eq!(x, half + half);
+            prop_assert_eq!(x, (x + x).halve());
+        }
+    }
+
+    #[test]
+    fn pow_i64_of_zero() {
+        assert_eq!(BFieldElement::ONE, BFieldElement::ZERO.pow_i64(0));
+        assert_eq!(BFieldElement::ZERO, BFieldElement::ZERO.pow_i64(3));
+        assert_eq!(None, BFieldElement::ZERO.checked_pow_i64(-1));
+        assert_eq!(None, BFieldElement::ZERO.checked_pow_i64(i64::MIN));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to find the multiplicative inverse of zero.")]
+    fn pow_i64_of_zero_with_negative_exponent_panics() {
+        let _ = BFieldElement::ZERO.pow_i64(-1);
+    }
+
+    #[test]
+    fn pow_i64_with_i64_min() {
+        let x = BFieldElement::new(3);
+        let expected = x.inverse().mod_pow(1 << 63);
+        assert_eq!(expected, x.pow_i64(i64::MIN));
+        assert_eq!(BFieldElement::ONE, x.pow_i64(i64::MIN) * x.mod_pow(1 << 63));
+    }
+
+    proptest! {
+        #[test]
+        fn pow_i64_negative_exponents_are_inverse(value in 1..BFieldElement::P, exponent: i64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(BFieldElement::ONE, x.pow_i64(-3) * x.pow_i64(3));
+            prop_assert_eq!(x.inverse(), x.pow_i64(-1));
+
+            let exponent = exponent.max(i64::MIN + 1);
+            prop_assert_eq!(BFieldElement::ONE, x.pow_i64(exponent) * x.pow_i64(-exponent));
+            prop_assert_eq!(Some(x.pow_i64(exponent)), x.checked_pow_i64(exponent));
+        }
+
+        #[test]
+        fn pow_i64_agrees_with_mod_pow(value: u64, exponent in 0_i64..) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(x.mod_pow(exponent as u64), x.pow_i64(exponent));
+        }
+    }
+
+    #[test]
+    fn non_canonical_raw_words_are_rejected() {
+        for raw in [
+            BFieldElement::P,
+            BFieldElement::P + 1,
+            u64::MAX - 1,
+            u64::MAX,
+        ] {
+            assert!(!BFieldElement::is_canonical_raw(raw));
+            assert_eq!(None, BFieldElement::try_from_raw_u64(raw));
+            assert_eq!(None, BFieldElement::try_from_raw_bytes(&raw.to_le_bytes()));
+        }
+    }
+
+    #[test]
+    fn unchecked_raw_constructors_are_unchanged() {
+        let raw = BFieldElement::P - 1;
+        assert_eq!(raw, BFieldElement::from_raw_u64(raw).raw_u64());
+        assert_eq!(
+            raw,
+            BFieldElement::from_raw_bytes(&raw.to_le_bytes()).raw_u64()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn canonical_raw_words_round_trip(value: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert!(BFieldElement::is_canonical_raw(x.raw_u64()));
+            prop_assert_eq!(Some(x), BFieldElement::try_from_raw_u64(x.raw_u64()));
+            prop_assert_eq!(Some(x), BFieldElement::try_from_raw_bytes(&x.raw_bytes()));
+        }
+
+        #[test]
+        fn raw_words_in_non_canonical_range_are_rejected(raw in BFieldElement::P..) {
+            prop_assert_eq!(None, BFieldElement::try_from_raw_u64(raw));
+        }
+    }
+
+    const MACRO_CONSTANT: BFieldElement = bfe!(-2);
+    const MACRO_ARRAY: [BFieldElement; 3] = bfe_array![0, 1 << 63, -(1 << 63)];
+    const _: () = assert!(MACRO_CONSTANT.value() == BFieldElement::P - 2);
+    const _: () = assert!(MACRO_ARRAY[1].const_add(MACRO_ARRAY[2]).value() == 0);
+
+    #[test]
+    fn bfe_macro_accepts_negative_literals_and_expressions() {
+        assert_eq!(-BFieldElement::ONE, bfe!(-1));
+        assert_eq!(BFieldElement::new(BFieldElement::MAX), bfe!(-1));
+        assert_eq!(BFieldElement::new(6), bfe!(2 * 3));
+        assert_eq!(BFieldElement::new(1 << 40), bfe!(1 << 40));
+        assert_eq!(
+            BFieldElement::new(BFieldElement::MAX),
+            bfe!(18446744069414584320)
+        );
+        assert_eq!(BFieldElement::ZERO, bfe!(18446744069414584321));
+        assert_eq!(BFieldElement::ZERO, bfe!(-18446744069414584321));
+        assert_eq!(-BFieldElement::new(42), bfe!(-(6 * 7)));
+    }
+
+    #[test]
+    fn bfe_array_and_bfe_vec_macros() {
+        let expected = [1_i64, -2, 3].map(BFieldElement::from);
+        assert_eq!(expected, bfe_array![1, -2, 3]);
+        assert_eq!(expected.to_vec(), bfe_vec![1, -2, 3]);
+        assert_eq!([bfe!(-5); 3], bfe_array![-5; 3]);
+        assert_eq!(vec![bfe!(-5); 3], bfe_vec![-5; 3]);
+
+        assert!(bfe_array![].is_empty());
+        assert!(bfe_vec![].is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn from_i128_agrees_with_signed_and_unsigned_conversions(value: i64, unsigned: u64) {
+            prop_assert_eq!(BFieldElement::from(value), BFieldElement::from_i128(value.into()));
+            prop_assert_eq!(BFieldElement::new(unsigned), BFieldElement::from_i128(unsigned.into()));
+        }
+
+        #[test]
+        fn from_i128_reduces_modulo_p(value: i128) {
+            let p = i128::from(BFieldElement::P);
+            let expected = value.rem_euclid(p) as u64;
+            prop_assert_eq!(expected, BFieldElement::from_i128(value).value());
+        }
+    }
+
+    fn reference_reduction(value: u128) -> u64 {
+        let reduced = BigUint::from(value) % BigUint::from(BFieldElement::P);
+        reduced.try_into().unwrap()
+    }
+
+    #[test]
+    fn from_u128_extreme_values() {
+        let p = u128::from(BFieldElement::P);
+        let values = [
+            0,
+            1,
+            p - 1,
+            p,
+            p + 1,
+            1 << 64,
+            (1 << 64) - 1,
+            1 << 96,
+            (1 << 96) - 1,
+            1 << 127,
+            p * p,
+            p * p - 1,
+            u128::MAX,
+        ];
+        for value in values {
+            let expected = reference_reduction(value);
+            assert_eq!(expected, BFieldElement::from_u128(value).value(), "{value}");
+            assert_eq!(expected, BFieldElement::from(value).value(), "{value}");
+        }
+    }
+
+    #[test]
+    fn from_u128_is_const() {
+        const TWO_POW_64: BFieldElement = BFieldElement::from_u128(1 << 64);
+        assert_eq!(BFieldElement::new(0xffff_ffff), TWO_POW_64);
+    }
+
+    proptest! {
+        #[test]
+        fn from_u128_agrees_with_big_uint_reduction(value: u128) {
+            let expected = reference_reduction(value);
+            prop_assert_eq!(expected, BFieldElement::from_u128(value).value());
+        }
+
+        #[test]
+        fn from_u128_reduces_sums_of_products(a: [u64; 4], b: [u64; 4]) {
+            let dot_product = a.iter().zip(&b).map(|(&a, &b)| u128::from(a) * u128::from(b));
+            let dot_product = dot_product.fold(0_u128, u128::wrapping_add);
+
+            let expected = reference_reduction(dot_product);
+            prop_assert_eq!(expected, BFieldElement::from_u128(dot_product).value());
+        }
+    }
+
+    #[test]
+    fn lo_hi_split_of_canonical_and_raw_representation() {
+        let x = BFieldElement::new(0x0123_4567_89ab_cdef);
+        assert_eq!((0x89ab_cdef, 0x0123_4567), x.split_lo_hi());
+        assert_eq!(x, BFieldElement::from_lo_hi(0x89ab_cdef, 0x0123_4567));
+
+        let raw = x.raw_u64();
+        assert_eq!((raw as u32, (raw >> 32) as u32), x.split_raw_lo_hi());
+        assert_ne!(x.split_lo_hi(), x.split_raw_lo_hi());
+
+        assert_eq!((0, 0), BFieldElement::ZERO.split_lo_hi());
+        assert_eq!((0, 0xffff_ffff), (-BFieldElement::ONE).split_lo_hi());
+    }
+
+    #[test]
+    fn from_lo_hi_reduces() {
+        assert_eq!(
+            BFieldElement::ZERO,
+            BFieldElement::from_lo_hi(1, 0xffff_ffff)
+        );
+        assert_eq!(bfe!(1), BFieldElement::from_lo_hi(2, 0xffff_ffff));
+    }
+
+    proptest! {
+        #[test]
+        fn lo_hi_round_trip(value: u64) {
+            let x = BFieldElement::new(value);
+            let (lo, hi) = x.split_lo_hi();
+            prop_assert_eq!(x, BFieldElement::from_lo_hi(lo, hi));
+
+            let (raw_lo, raw_hi) = x.split_raw_lo_hi();
+            prop_assert_eq!(x, BFieldElement::from_raw_lo_hi(raw_lo, raw_hi));
+            prop_assert_eq!(
+                (raw_lo, raw_hi),
+                BFieldElement::new(x.raw_u64()).split_lo_hi()
+            );
+        }
+    }
+
+    #[test]
+    fn bits_at_boundaries() {
+        let p_minus_one = BFieldElement::new(BFieldElement::P - 1);
+        let bits = p_minus_one.to_bits();
+        assert!(!bits[0]);
+        assert!(bits[32..].iter().all(|&bit| bit));
+        assert!(bits[1..32].iter().all(|&bit| !bit));
+        assert_eq!(Ok(p_minus_one), BFieldElement::from_bits(&bits));
+
+        let mut p_bits = bits;
+        p_bits[0] = true;
+        let err = BitDecompositionError::NotCanonical(BFieldElement::P);
+        assert_eq!(Err(err), BFieldElement::from_bits(&p_bits));
+        let err = BitDecompositionError::NotCanonical(u64::MAX);
+        assert_eq!(Err(err), BFieldElement::from_bits(&[true; 64]));
+
+        assert_eq!(Ok(BFieldElement::ZERO), BFieldElement::from_bits(&[]));
+        assert_eq!(Ok(bfe!(5)), BFieldElement::from_bits(&[true, false, true]));
+        let err = BitDecompositionError::TooManyBits(65);
+        assert_eq!(Err(err), BFieldElement::from_bits(&[false; 65]));
+    }
+
+    #[test]
+    fn bit_vec_is_range_checked() {
+        let x = bfe!(0b1011);
+        assert_eq!(Ok(vec![true, true, false, true]), x.to_bit_vec(4));
+        assert_eq!(Ok(vec![true, true, false, true, false]), x.to_bit_vec(5));
+        let err = BitDecompositionError::ValueTooLarge {
+            value: 11,
+            num_bits: 3,
+        };
+        assert_eq!(Err(err), x.to_bit_vec(3));
+
+        assert_eq!(Ok(vec![]), BFieldElement::ZERO.to_bit_vec(0));
+        assert_eq!(Ok(vec![false; 70]), BFieldElement::ZERO.to_bit_vec(70));
+
+        let p_minus_one = BFieldElement::new(BFieldElement::P - 1);
+        assert_eq!(
+            Ok(p_minus_one.to_bits().to_vec()),
+            p_minus_one.to_bit_vec(64)
+        );
+        assert!(p_minus_one.to_bit_vec(63).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn bits_round_trip(value: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(Ok(x), BFieldElement::from_bits(&x.to_bits()));
+        }
+
+        #[test]
+        fn bit_vec_round_trip(value: u64, num_bits in 0_usize..=64) {
+            let value = value.checked_shr(64 - num_bits as u32).unwrap_or(0);
+            let x = BFieldElement::new(value);
+            let bits = x.to_bit_vec(num_bits).unwrap();
+            prop_assert_eq!(num_bits, bits.len());
+            prop_assert_eq!(Ok(x), BFieldElement::from_bits(&bits));
+        }
+    }
+
+    #[test]
+    fn canonical_bytes_encode_value_not_montgomery_representation() {
+        let x = BFieldElement::new(0x0102_0304_0506_0708);
+        assert_eq!([8, 7, 6, 5, 4, 3, 2, 1], x.to_le_bytes());
+        assert_eq!([1, 2, 3, 4, 5, 6, 7, 8], x.to_be_bytes());
+        assert_ne!(x.raw_bytes(), x.to_le_bytes());
+        assert_eq!([0; 8], BFieldElement::ZERO.to_le_bytes());
+        assert_eq!([0, 0, 0, 0, 0, 0, 0, 1], BFieldElement::ONE.to_be_bytes());
+    }
+
+    #[test]
+    fn non_canonical_bytes_are_rejected() {
+        for value in [BFieldElement::P, BFieldElement::P + 1, u64::MAX] {
+            let err = NotCanonicalError(value);
+            assert_eq!(Err(err), BFieldElement::from_le_bytes(&value.to_le_bytes()));
+            assert_eq!(Err(err), BFieldElement::from_be_bytes(&value.to_be_bytes()));
+        }
+
+        let largest = BFieldElement::P - 1;
+        let expected = Ok(BFieldElement::new(largest));
+        assert_eq!(
+            expected,
+            BFieldElement::from_le_bytes(&largest.to_le_bytes())
+        );
+        assert_eq!(
+            expected,
+            BFieldElement::from_be_bytes(&largest.to_be_bytes())
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn canonical_bytes_round_trip(value: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(Ok(x), BFieldElement::from_le_bytes(&x.to_le_bytes()));
+            prop_assert_eq!(Ok(x), BFieldElement::from_be_bytes(&x.to_be_bytes()));
+        }
+    }
+
+    #[test]
+    fn montify_empty_slice() {
+        assert!(BFieldElement::montify_slice(&[]).is_empty());
+        assert!(BFieldElement::montify_in_place(&mut []).is_empty());
+        assert!(BFieldElement::canonical_values(&[]).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn batch_montgomery_conversion_agrees_with_element_wise_conversion(
+            values in proptest::collection::vec(any::<u64>(), 0..10_000),
+        ) {
+            let expected = values.iter().map(|&v| BFieldElement::new(v)).collect::<Vec<_>>();
+            prop_assert_eq!(&expected, &BFieldElement::montify_slice(&values));
+
+            let mut in_place = values.clone();
+            prop_assert_eq!(&expected, BFieldElement::montify_in_place(&mut in_place));
+
+            let canonical = expected.iter().map(|x| x.value()).collect::<Vec<_>>();
+            prop_assert_eq!(canonical, BFieldElement::canonical_values(&expected));
+        }
+    }
+
+    #[test]
+    fn raw_u64_slice_views_share_memory() {
+        let mut elements = bfe_vec![0, 1, 2, -1];
+        let raw = BFieldElement::slice_as_raw_u64s(&elements);
+        assert_eq!(elements.as_ptr().cast(), raw.as_ptr());
+        for (element, &word) in elements.iter().zip(raw) {
+            assert_eq!(element.raw_u64(), word);
+        }
+
+        let raw = BFieldElement::slice_as_raw_u64s_mut(&mut elements);
+        raw[0] = BFieldElement::ONE.raw_u64();
+        assert_eq!(BFieldElement::ONE, elements[0]);
+
+        let mut raw = vec![BFieldElement::ONE.raw_u64(); 3];
+        BFieldElement::slice_from_raw_u64s_mut(&mut raw)[1] = BFieldElement::ZERO;
+        assert_eq!(0, raw[1]);
+    }
+
+    #[test]
+    fn checked_raw_u64_slice_views_reject_non_canonical_words() {
+        let mut raw = vec![0, 1, BFieldElement::P - 1];
+        assert!(BFieldElement::try_slice_from_raw_u64s(&raw).is_some());
+        assert!(BFieldElement::try_slice_from_raw_u64s_mut(&mut raw).is_some());
+        assert!(BFieldElement::try_slice_from_raw_u64s(&[]).is_some());
+
+        for non_canonical in [BFieldElement::P, u64::MAX] {
+            raw[1] = non_canonical;
+            assert_eq!(None, BFieldElement::try_slice_from_raw_u64s(&raw));
+            assert_eq!(None, BFieldElement::try_slice_from_raw_u64s_mut(&mut raw));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn raw_u64_slice_views_round_trip(values in proptest::collection::vec(any::<u64>(), 0..10_000)) {
+            let elements = values.into_iter().map(BFieldElement::new).collect::<Vec<_>>();
+            let raw = BFieldElement::slice_as_raw_u64s(&elements);
+            prop_assert_eq!(elements.as_slice(), BFieldElement::slice_from_raw_u64s(raw));
+            prop_assert_eq!(Some(elements.as_slice()), BFieldElement::try_slice_from_raw_u64s(raw));
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    mod zeroize_tests {
+        use zeroize::Zeroize;
+
+        use super::*;
+
+        #[test]
+        fn zeroize_clears_raw_word() {
+            let mut x = BFieldElement::new(0xdead_beef);
+            assert_ne!(0, x.raw_u64());
+            x.zeroize();
+            assert_eq!(0, x.raw_u64());
+            assert_eq!(BFieldElement::ZERO, x);
+        }
+
+        #[test]
+        fn zeroize_clears_arrays_and_slices() {
+            let mut array = bfe_array![1, 2, 3, -1];
+            array.zeroize();
+            assert!(array.iter().all(|x| x.raw_u64() == 0));
+
+            let mut vector = bfe_vec![4, 5, 6, -1];
+            vector.as_mut_slice().zeroize();
+            assert!(vector.iter().all(|x| x.raw_u64() == 0));
+        }
+
+        #[test]
+        fn secret_element_is_zeroizable_and_redacted() {
+            let mut secret = SecretBFieldElement::from(bfe!(1_234_567));
+            assert_eq!(bfe!(1_234_567), *secret.expose_secret());
+            assert!(!format!("{secret:?}").contains("1234567"));
+
+            secret.zeroize();
+            assert_eq!(0, secret.expose_secret().raw_u64());
+        }
+
+        #[cfg(feature = "subtle")]
+        #[test]
+        fn secret_elements_compare_in_constant_time() {
+            use subtle::ConstantTimeEq;
+
+            let secret = SecretBFieldElement::new(bfe!(1_234_567));
+            assert!(bool::from(secret.ct_eq(&secret.clone())));
+            assert!(!bool::from(secret.ct_eq(&SecretBFieldElement::default())));
+        }
+    }
+
+    #[cfg(feature = "borsh")]
+    mod borsh_tests {
+        use super::*;
+
+        #[test]
+        fn borsh_encodes_canonical_value_as_little_endian_u64() {
+            let x = BFieldElement::new(0x0102_0304);
+            let bytes = borsh::to_vec(&x).unwrap();
+            assert_eq!(vec![4, 3, 2, 1, 0, 0, 0, 0], bytes);
+            assert_eq!(x, borsh::from_slice(&bytes).unwrap());
+        }
+
+        #[test]
+        fn borsh_rejects_non_canonical_values() {
+            let bytes = BFieldElement::P.to_le_bytes();
+            assert!(borsh::from_slice::<BFieldElement>(&bytes).is_err());
+            assert!(borsh::from_slice::<BFieldElement>(&bytes[..7]).is_err());
+        }
+
+        proptest! {
+            #[test]
+            fn borsh_round_trip(value in 0..BFieldElement::P) {
+                let x = BFieldElement::new(value);
+                let bytes = borsh::to_vec(&x).unwrap();
+                prop_assert_eq!(value.to_le_bytes().to_vec(), bytes.clone());
+                prop_assert_eq!(x, borsh::from_slice(&bytes).unwrap());
+            }
+        }
+    }
+
+    #[cfg(feature = "rkyv")]
+    mod rkyv_tests {
+        use rkyv::Deserialize;
+
+        use super::*;
+
+        #[test]
+        fn archived_element_holds_canonical_value() {
+            let x = BFieldElement::new(42);
+            let bytes = rkyv::to_bytes::<_, 16>(&x).unwrap();
+            assert_eq!(42_u64.to_ne_bytes(), bytes.as_slice());
+
+            let archived = rkyv::check_archived_root::<BFieldElement>(&bytes).unwrap();
+            assert_eq!(42, archived.value());
+            assert_eq!(*archived, x);
+            assert_eq!(Ok(x), archived.deserialize(&mut rkyv::Infallible));
+        }
+
+        #[test]
+        fn validation_rejects_non_canonical_values() {
+            let mut bytes = rkyv::to_bytes::<_, 16>(&BFieldElement::new(42)).unwrap();
+            bytes.copy_from_slice(&BFieldElement::P.to_ne_bytes());
+            assert!(rkyv::check_archived_root::<BFieldElement>(&bytes).is_err());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_tests {
+        use super::*;
+
+        #[test]
+        fn json_encodes_canonical_value_as_string() {
+            let x = BFieldElement::new(42);
+            assert_eq!(r#""42""#, serde_json::to_string(&x).unwrap());
+            assert_eq!(x, serde_json::from_str(r#""42""#).unwrap());
+        }
+
+        #[test]
+        fn json_accepts_numbers() {
+            let x = BFieldElement::new(42);
+            assert_eq!(x, serde_json::from_str("42").unwrap());
+
+            let large = BFieldElement::P - 1;
+            let json = large.to_string();
+            assert_eq!(
+                BFieldElement::new(large),
+                serde_json::from_str(&json).unwrap()
+            );
+        }
+
+        #[test]
+        fn json_round_trip_above_two_to_the_53() {
+            for value in [(1 << 53) + 1, 0xdead_beef_cafe_babe, BFieldElement::P - 1] {
+                let x = BFieldElement::new(value);
+                let json = serde_json::to_string(&x).unwrap();
+                assert_eq!(format!(r#""{value}""#), json);
+                assert_eq!(x, serde_json::from_str(&json).unwrap());
+            }
+        }
+
+        #[test]
+        fn json_rejects_malformed_input() {
+            for json in ["-1", r#""-1""#, r#""""#, r#""12a""#, "1.5", "null"] {
+                assert!(
+                    serde_json::from_str::<BFieldElement>(json).is_err(),
+                    "{json}"
+                );
+            }
+        }
+
+        #[test]
+        fn bincode_encoding_has_eight_bytes() {
+            let bytes = bincode::serialize(&BFieldElement::new(BFieldElement::P - 1)).unwrap();
+            assert_eq!(8, bytes.len());
+        }
+
+        #[test]
+        fn non_canonical_values_fail_to_deserialize() {
+            for value in [BFieldElement::P, BFieldElement::P + 1, u64::MAX] {
+                for json in [value.to_string(), format!(r#""{value}""#)] {
+                    let err = serde_json::from_str::<BFieldElement>(&json).unwrap_err();
+                    assert!(err.to_string().contains("not canonical"), "{err}");
+                }
+
+                let bytes = bincode::serialize(&value).unwrap();
+                assert!(bincode::deserialize::<BFieldElement>(&bytes).is_err());
+            }
+        }
+
+        #[test]
+        fn bincode_encodes_canonical_value() {
+            let x = BFieldElement::new(0xdead_beef);
+            let bytes = bincode::serialize(&x).unwrap();
+            assert_eq!(0xdead_beef_u64.to_le_bytes().as_slice(), bytes);
+        }
+
+        proptest! {
+            #[test]
+            fn json_round_trip(values: [u64; 10]) {
+                let elements = values.map(BFieldElement::new);
+                let json = serde_json::to_string(&elements).unwrap();
+                let decoded: [BFieldElement; 10] = serde_json::from_str(&json).unwrap();
+                prop_assert_eq!(elements, decoded);
+            }
+
+            #[test]
+            fn bincode_round_trip(values: Vec<u64>) {
+                let elements = values.into_iter().map(BFieldElement::new).collect::<Vec<_>>();
+                let bytes = bincode::serialize(&elements).unwrap();
+                let decoded: Vec<BFieldElement> = bincode::deserialize(&bytes).unwrap();
+                prop_assert_eq!(elements, decoded);
+            }
+        }
+    }
+
+    #[cfg(feature = "subtle")]
+    mod subtle_tests {
+        use subtle::ConstantTimeEq;
+
+        use super::*;
+
+        #[test]
+        fn ct_eq_on_near_equal_elements() {
+            let x = bfe!(0x1234_5678_9abc_def0);
+            assert!(bool::from(x.ct_eq(&x)));
+            for bit in 0..64 {
+                let y = BFieldElement::from_raw_u64(x.raw_u64() ^ (1 << bit));
+                assert!(!bool::from(x.ct_eq(&y)), "bit {bit}");
+            }
+        }
+
+        #[test]
+        fn conditional_select_picks_the_right_element() {
+            use subtle::ConditionallySelectable;
+
+            let a = bfe!(1);
+            let b = bfe!(-1);
+            assert_eq!(a, BFieldElement::conditional_select(&a, &b, 0.into()));
+            assert_eq!(b, BFieldElement::conditional_select(&a, &b, 1.into()));
+        }
+
+        proptest! {
+            #[test]
+            fn ct_eq_agrees_with_eq(a: u64, b: u64) {
+                let a = BFieldElement::new(a);
+                let b = BFieldElement::new(b);
+                prop_assert_eq!(a == b, bool::from(a.ct_eq(&b)));
+                prop_assert!(bool::from(a.ct_eq(&a)));
+            }
+        }
+    }
+
+    #[test]
+    fn mod_pow_ct_edge_cases() {
+        for x in [0, 1, 2, BFieldElement::MAX].map(BFieldElement::new) {
+            for exponent in [
+                0,
+                1,
+                2,
+                BFieldElement::P - 2,
+                BFieldElement::P - 1,
+                u64::MAX,
+            ] {
+                assert_eq!(
+                    x.mod_pow(exponent),
+                    x.mod_pow_ct(exponent),
+                    "{x}^{exponent}"
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn mod_pow_ct_agrees_with_mod_pow(value: u64, exponent: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(x.mod_pow(exponent), x.mod_pow_ct(exponent));
+        }
+    }
+
+    /// A coarse check that the running time of [`BFieldElement::mod_pow_ct`] does not depend on
+    /// the exponent's Hamming weight. Timing measurements are noisy, hence the test is ignored
+    /// by default.
+    #[test]
+    #[ignore = "timing measurements are unreliable"]
+    fn mod_pow_ct_timing_is_independent_of_exponent() {
+        use std::hint::black_box;
+        use std::time::Instant;
+
+        fn time_exponent(exponent: u64) -> std::time::Duration {
+            let x = BFieldElement::new(0x1234_5678_9abc_def0);
+            let start = Instant::now();
+            for _ in 0..100_000 {
+                black_box(black_box(x).mod_pow_ct(black_box(exponent)));
+            }
+            start.elapsed()
+        }
+
+        let sparse = time_exponent(1);
+        let dense = time_exponent(u64::MAX);
+        let ratio = sparse.as_secs_f64() / dense.as_secs_f64();
+        assert!((0.8..1.25).contains(&ratio), "timing ratio: {ratio}");
+    }
+
+    proptest! {
+        #[test]
+        #[allow(clippy::op_ref)]
+        fn reference_operators_agree_with_owned_operators(a: u64, b in 1..BFieldElement::P) {
+            let a = BFieldElement::new(a);
+            let b = BFieldElement::new(b);
+
+            prop_assert_eq!(a + b, &a + b);
+            prop_assert_eq!(a + b, a + &b);
+            prop_assert_eq!(a + b, &a + &b);
+
+            prop_assert_eq!(a - b, &a - b);
+            prop_assert_eq!(a - b, a - &b);
+            prop_assert_eq!(a - b, &a - &b);
+
+            prop_assert_eq!(a * b, &a * b);
+            prop_assert_eq!(a * b, a * &b);
+            prop_assert_eq!(a * b, &a * &b);
+
+            prop_assert_eq!(a / b, &a / b);
+            prop_assert_eq!(a / b, a / &b);
+            prop_assert_eq!(a / b, &a / &b);
+
+            prop_assert_eq!(-a, -&a);
+        }
+
+        #[test]
+        fn reference_assign_operators_agree_with_owned_operators(a: u64, b: u64) {
+            let a = BFieldElement::new(a);
+            let b = BFieldElement::new(b);
+
+            let mut sum = a;
+            sum += &b;
+            prop_assert_eq!(a + b, sum);
+
+            let mut difference = a;
+            difference -= &b;
+            prop_assert_eq!(a - b, difference);
+
+            let mut product = a;
+            product *= &b;
+            prop_assert_eq!(a * b, product);
+        }
+    }
+
+    #[test]
+    fn reference_operators_work_with_iterator_adapters() {
+        let elements = bfe_vec![1, 2, 3, 4];
+        let squares = elements.iter().map(|x| x * x).collect::<Vec<_>>();
+        assert_eq!(bfe_vec![1, 4, 9, 16], squares);
+
+        let inner_product = elements.iter().zip(&squares).map(|(a, b)| a * b).sum();
+        assert_eq!(bfe!(100), inner_product);
+    }
+
+    #[test]
+    fn from_primitive_reduces() {
+        let p = BFieldElement::P;
+        assert_eq!(Some(BFieldElement::ZERO), BFieldElement::from_u64(p));
+        assert_eq!(Some(bfe!(41)), BFieldElement::from_u64(p + 41));
+        assert_eq!(Some(-BFieldElement::ONE), BFieldElement::from_i64(-1));
+        assert_eq!(
+            Some(-BFieldElement::ONE),
+            <BFieldElement as FromPrimitive>::from_i128(-1)
+        );
+        assert_eq!(Some(-BFieldElement::ONE), BFieldElement::from_i8(-1));
+        assert_eq!(
+            Some(bfe!(7)),
+            <BFieldElement as FromPrimitive>::from_u128(u128::from(p) * 3 + 7)
+        );
+        assert_eq!(Some(bfe!(7)), BFieldElement::from_f64(7.9));
+        assert_eq!(Some(-bfe!(7)), BFieldElement::from_f64(-7.9));
+        assert_eq!(None, BFieldElement::from_f64(f64::NAN));
+    }
+
+    #[test]
+    fn to_primitive_boundaries() {
+        let i64_max = BFieldElement::new(i64::MAX as u64);
+        assert_eq!(Some(i64::MAX), i64_max.to_i64());
+        assert_eq!(None, (i64_max + BFieldElement::ONE).to_i64());
+
+        let max = BFieldElement::new(BFieldElement::MAX);
+        assert_eq!(Some(BFieldElement::MAX), max.to_u64());
+        assert_eq!(None, max.to_i64());
+        assert_eq!(Some(BFieldElement::MAX.into()), max.to_i128());
+        assert_eq!(None, max.to_u32());
+
+        // negative inputs are not recovered, see `TryFrom<BFieldElement> for i64`
+        assert_eq!(None, BFieldElement::from(-1_i64).to_i64());
+        assert_eq!(Some(u8::MAX), bfe!(255).to_u8());
+        assert_eq!(None, bfe!(256).to_u8());
+    }
+
+    proptest! {
+        #[test]
+        fn from_primitive_agrees_with_from(value: i64, unsigned: u64, wide: u128) {
+            prop_assert_eq!(Some(BFieldElement::from(value)), BFieldElement::from_i64(value));
+            prop_assert_eq!(Some(BFieldElement::new(unsigned)), BFieldElement::from_u64(unsigned));
+
+            let expected = (wide % u128::from(BFieldElement::P)) as u64;
+            prop_assert_eq!(Some(expected), <BFieldElement as FromPrimitive>::from_u128(wide).map(|x| x.value()));
+        }
+
+        #[test]
+        fn to_u64_is_canonical_value(value: u64) {
+            let x = BFieldElement::new(value);
+            prop_assert_eq!(Some(x.value()), x.to_u64());
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    mod bytemuck_tests {
+        use super::*;
+
+        #[test]
+        fn bytes_are_raw_montgomery_representation() {
+            let elements = bfe_array![0, 1, 2, -1];
+            let bytes = BFieldElement::as_bytes(&elements);
+            assert_eq!(elements.len() * BFieldElement::BYTES, bytes.len());
+
+            for (element, chunk) in elements.iter().zip(bytes.chunks_exact(8)) {
+                let raw = u64::from_ne_bytes(chunk.try_into().unwrap());
+                assert_eq!(element.raw_u64(), raw);
+            }
+        }
+
+        #[test]
+        fn zeroed_element_is_zero() {
+            assert_eq!(BFieldElement::ZERO, bytemuck::Zeroable::zeroed());
+        }
+
+        #[test]
+        fn misaligned_casts_fail() {
+            let elements = bfe_array![1, 2, 3];
+            let bytes = BFieldElement::as_bytes(&elements);
+
+            let misaligned = BFieldElement::try_from_bytes(&bytes[1..9]);
+            assert_eq!(
+                Err(bytemuck::PodCastError::TargetAlignmentGreaterAndInputNotAligned),
+                misaligned
+            );
+
+            let with_slop = BFieldElement::try_from_bytes(&bytes[..12]);
+            assert_eq!(
+                Err(bytemuck::PodCastError::OutputSliceWouldHaveSlop),
+                with_slop
+            );
+        }
+
+        proptest! {
+            #[test]
+            fn bytes_round_trip(values in proptest::collection::vec(any::<u64>(), 0..100)) {
+                let elements = values.into_iter().map(BFieldElement::new).collect::<Vec<_>>();
+                let bytes = BFieldElement::as_bytes(&elements);
+                let round_tripped = BFieldElement::try_from_bytes(bytes).unwrap();
+                prop_assert_eq!(elements.as_slice(), round_tripped);
+            }
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    mod proptest_tests {
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn arbitrary_elements_are_canonical(x: BFieldElement) {
+                prop_assert!(x.value() < BFieldElement::P);
+                prop_assert!(BFieldElement::is_canonical_raw(x.raw_u64()));
+            }
+
+            #[test]
+            fn corner_cases_are_canonical(x in bfield_element_corner_cases()) {
+                prop_assert!(x.value() < BFieldElement::P);
+                prop_assert!(BFieldElement::is_canonical_raw(x.raw_u64()));
+            }
+
+            #[test]
+            fn addition_is_associative(
+                a in bfield_element_corner_cases(),
+                b in bfield_element_corner_cases(),
+                c: BFieldElement,
+            ) {
+                prop_assert_eq!((a + b) + c, a + (b + c));
+            }
+
+            #[test]
+            fn multiplication_is_associative(
+                a in bfield_element_corner_cases(),
+                b in bfield_element_corner_cases(),
+                c: BFieldElement,
+            ) {
+                prop_assert_eq!((a * b) * c, a * (b * c));
+            }
+
+            #[test]
+            fn multiplication_distributes_over_addition(
+                a in bfield_element_corner_cases(),
+                b in bfield_element_corner_cases(),
+                c in bfield_element_corner_cases(),
+            ) {
+                prop_assert_eq!(a * (b + c), a * b + a * c);
+            }
+
+            #[test]
+            fn subtraction_undoes_addition(
+                a in bfield_element_corner_cases(),
+                b in bfield_element_corner_cases(),
+            ) {
+                prop_assert_eq!(a, (a + b) - b);
+            }
+
+            #[test]
+            fn non_zero_elements_have_inverses(a in bfield_element_corner_cases()) {
+                prop_assume!(!a.is_zero());
+                prop_assert_eq!(BFieldElement::ONE, a * a.inverse());
+            }
+        }
+
+        #[test]
+        fn arbitrary_shrinks_toward_zero() {
+            use proptest::strategy::ValueTree;
+            use proptest::test_runner::TestRunner;
+
+            let mut runner = TestRunner::deterministic();
+            let mut tree = any::<BFieldElement>().new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!(BFieldElement::ZERO, tree.current());
+        }
+    }
+
+    #[cfg(feature = "quickcheck")]
+    mod quickcheck_tests {
+        use quickcheck::Arbitrary;
+        use quickcheck::quickcheck;
+
+        use super::*;
+
+        quickcheck! {
+            fn addition_is_commutative(a: BFieldElement, b: BFieldElement) -> bool {
+                a + b == b + a
+            }
+
+            fn multiplication_is_commutative(a: BFieldElement, b: BFieldElement) -> bool {
+                a * b == b * a
+            }
+
+            fn shrunk_elements_are_smaller(a: BFieldElement) -> bool {
+                a.shrink().all(|s| s.value() < a.value())
+            }
+        }
+
+        #[test]
+        fn arbitrary_elements_do_not_favor_edge_values() {
+            let mut g = quickcheck::Gen::new(100);
+            let num_edge_values = (0..10_000)
+                .map(|_| BFieldElement::arbitrary(&mut g))
+                .filter(|&x| x == BFieldElement::ZERO || x == BFieldElement::ONE)
+                .count();
+            assert!(
+                num_edge_values < 10,
+                "{num_edge_values} of 10000 are 0 or 1"
+            );
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    mod rand_tests {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        use super::*;
+
+        #[test]
+        fn sampling_is_deterministic_under_seeded_rng() {
+            let first = StdRng::seed_from_u64(42).r#gen::<[BFieldElement; 10]>();
+            let second = StdRng::seed_from_u64(42).r#gen::<[BFieldElement; 10]>();
+            assert_eq!(first, second);
+
+            let third = StdRng::seed_from_u64(43).r#gen::<[BFieldElement; 10]>();
+            assert_ne!(first, third);
+        }
+
+        #[test]
+        fn sampled_arrays_can_seed_hash_10() {
+            let input = StdRng::seed_from_u64(0).r#gen::<[BFieldElement; 10]>();
+            assert_eq!(crate::Tip5::hash_10(&input), crate::Tip5::hash_10(&input));
+        }
+
+        #[test]
+        fn low_bits_of_samples_are_uniform() {
+            const NUM_SAMPLES: usize = 160_000;
+            const NUM_BUCKETS: usize = 16;
+
+            let mut rng = StdRng::seed_from_u64(0x5eed);
+            let mut buckets = [0_usize; NUM_BUCKETS];
+            for _ in 0..NUM_SAMPLES {
+                let sample: BFieldElement = rng.r#gen();
+                buckets[sample.value() as usize % NUM_BUCKETS] += 1;
+            }
+
+            let expected = (NUM_SAMPLES / NUM_BUCKETS) as f64;
+            let chi_square = buckets
+                .into_iter()
+                .map(|observed| (observed as f64 - expected).powi(2) / expected)
+                .sum::<f64>();
+
+            // the critical value for 15 degrees of freedom at significance level 0.001 is 37.7
+            assert!(chi_square < 37.7, "χ² = {chi_square}");
+        }
+
+        #[test]
+        fn random_elements_are_deterministic_under_seeded_rng() {
+            let first = BFieldElement::random_elements(&mut StdRng::seed_from_u64(1), 1_000);
+            let second = BFieldElement::random_elements(&mut StdRng::seed_from_u64(1), 1_000);
+            assert_eq!(1_000, first.len());
+            assert_eq!(first, second);
+        }
+
+        #[test]
+        fn random_elements_agree_with_individual_sampling() {
+            let bulk = BFieldElement::random_elements(&mut StdRng::seed_from_u64(2), 200);
+
+            let mut rng = StdRng::seed_from_u64(2);
+            let individual = (0..200)
+                .map(|_| rng.r#gen())
+                .collect::<Vec<BFieldElement>>();
+            assert_eq!(individual, bulk);
+        }
+
+        #[test]
+        fn random_elements_are_canonical() {
+            let elements = BFieldElement::random_elements(&mut StdRng::seed_from_u64(3), 10_000);
+            for element in elements {
+                assert!(BFieldElement::is_canonical_raw(element.raw_u64()));
+                assert!(element.value() < BFieldElement::P);
+            }
+        }
+
+        #[test]
+        fn fill_random_of_empty_slice_is_a_no_op() {
+            BFieldElement::fill_random(&mut StdRng::seed_from_u64(4), &mut []);
+            assert!(BFieldElement::random_elements(&mut StdRng::seed_from_u64(4), 0).is_empty());
+        }
+
+        /// An RNG producing only non-canonical words at first, exercising the rejection path.
+        struct NonCanonicalFirst {
+            num_rejections: usize,
+            inner: StdRng,
+        }
+
+        impl rand::RngCore for NonCanonicalFirst {
+            fn next_u32(&mut self) -> u32 {
+                self.next_u64() as u32
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                if self.num_rejections > 0 {
+                    self.num_rejections -= 1;
+                    return u64::MAX;
+                }
+                self.inner.next_u64()
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                for chunk in dest.chunks_mut(8) {
+                    let bytes = self.next_u64().to_le_bytes();
+                    chunk.copy_from_slice(&bytes[..chunk.len()]);
+                }
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn fill_random_rejects_non_canonical_words() {
+            let inner = StdRng::seed_from_u64(5);
+            let mut rng = NonCanonicalFirst {
+                num_rejections: 100,
+                inner,
+            };
+            let elements = BFieldElement::random_elements(&mut rng, 10);
+
+            let expected = BFieldElement::random_elements(&mut StdRng::seed_from_u64(5), 10);
+            assert_eq!(expected, elements);
+        }
+    }
+}
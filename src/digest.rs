@@ -1,13 +1,157 @@
-use crate::b_field_element::BFieldElement;
+#[cfg(feature = "rkyv")]
+use crate::b_field_element::ArchivedBFieldElement;
+use crate::b_field_element::{BFieldElement, NotCanonicalError};
+
+/// Construct a [`Digest`] at compile time, either from its canonical hex encoding (see
+/// [`Digest::from_hex`]) or from the canonical values of its five limbs. Invalid literals,
+/// _e.g._, of the wrong length or with a limb that is not smaller than [`BFieldElement::P`],
+/// fail the build.
+///
+/// ```
+/// # use tip5_hash::{Digest, bfe_array, digest};
+/// const DIGEST: Digest = digest!(1, 2, 3, 4, 5);
+/// assert_eq!(Digest::new(bfe_array![1, 2, 3, 4, 5]), DIGEST);
+///
+/// let from_hex = digest!(
+///     "0x0100000000000000020000000000000003000000000000000400000000000000\
+///      0500000000000000"
+/// );
+/// assert_eq!(DIGEST, from_hex);
+/// ```
+///
+/// ```compile_fail
+/// # use tip5_hash::digest;
+/// let not_canonical = digest!(0, 0, 0, 0, 0xffff_ffff_0000_0001);
+/// ```
+#[macro_export]
+macro_rules! digest {
+    ($hex:literal) => {{
+        const DIGEST: $crate::Digest = $crate::Digest::__from_hex_or_panic($hex);
+        DIGEST
+    }};
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr $(,)?) => {{
+        const DIGEST: $crate::Digest = $crate::Digest::__from_u64s_or_panic([$a, $b, $c, $d, $e]);
+        DIGEST
+    }};
+}
 
 /// The result of hashing a sequence of elements, for [Tip5].
+///
+/// Digests are [ordered](Ord) as 320-bit integers whose little-endian limbs are the
+/// canonical [values](BFieldElement::value) of the digest's elements, _i.e._, the last
+/// element is the most significant limb. This agrees with the numeric interpretation of the
+/// little-endian [byte encoding](Digest::to_bytes).
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Digest(pub [BFieldElement; Digest::LEN]);
 
+impl Default for Digest {
+    fn default() -> Self {
+        Self::ALL_ZEROS
+    }
+}
+
+impl AsRef<[BFieldElement]> for Digest {
+    fn as_ref(&self) -> &[BFieldElement] {
+        &self.0
+    }
+}
+
+impl std::ops::Index<usize> for Digest {
+    type Output = BFieldElement;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl std::ops::Index<std::ops::Range<usize>> for Digest {
+    type Output = [BFieldElement];
+
+    fn index(&self, range: std::ops::Range<usize>) -> &Self::Output {
+        &self.0[range]
+    }
+}
+
+impl IntoIterator for Digest {
+    type Item = BFieldElement;
+    type IntoIter = std::array::IntoIter<BFieldElement, { Digest::LEN }>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Digest {
+    type Item = &'a BFieldElement;
+    type IntoIter = std::slice::Iter<'a, BFieldElement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Digest {
+    type Item = &'a mut BFieldElement;
+    type IntoIter = std::slice::IterMut<'a, BFieldElement>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
+impl PartialOrd for Digest {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Digest {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let self_limbs = self.0.iter().rev().map(|element| element.value());
+        let other_limbs = other.0.iter().rev().map(|element| element.value());
+        self_limbs.cmp(other_limbs)
+    }
+}
+
+impl Digest {
+    /// The number of leading zero bits of the digest, interpreted as a 320-bit integer like
+    /// for the [ordering](Ord). Counting starts at the most significant bit of the last
+    /// limb's canonical [value](BFieldElement::value). [`Self::ALL_ZEROS`] has 320 leading
+    /// zero bits.
+    pub fn leading_zero_bits(&self) -> u32 {
+        let mut num_zero_bits = 0;
+        for limb in self.0.iter().rev() {
+            let value = limb.value();
+            num_zero_bits += value.leading_zeros();
+            if value != 0 {
+                break;
+            }
+        }
+        num_zero_bits
+    }
+
+    /// Whether the digest, interpreted as a 320-bit integer like for the [ordering](Ord), is
+    /// at most `target`. This is the usual proof-of-work condition: the smaller the target,
+    /// the higher the difficulty.
+    pub fn meets_target(&self, target: &Digest) -> bool {
+        self <= target
+    }
+}
+
 impl Digest {
     /// The number of [elements](BFieldElement) in a digest.
     pub const LEN: usize = 5;
 
+    /// The number of bytes in the canonical [byte encoding](Self::to_bytes).
+    pub const BYTES: usize = Self::LEN * BFieldElement::BYTES;
+
+    /// The digest all of whose elements are zero, for example as a sentinel for empty or
+    /// uninitialized slots. This is also the [`Default`].
+    ///
+    /// Note that this is _not_ the hash of the empty input, which is
+    /// `Tip5::hash_varlen(&[])`.
+    pub const ALL_ZEROS: Self = Self([BFieldElement::new(0); Self::LEN]);
+
     /// Creates a new digest from an array of elements.
     pub const fn new(elements: [BFieldElement; Self::LEN]) -> Self {
         Self(elements)
@@ -16,4 +160,2199 @@ impl Digest {
     pub const fn values(self) -> [BFieldElement; Self::LEN] {
         self.0
     }
+
+    /// Creates a new digest from the given limbs, [reducing](BFieldElement::new) each limb
+    /// modulo [`BFieldElement::P`]. Usable in `const` items.
+    ///
+    /// To reject non-canonical limbs instead, use the [`TryFrom`] implementation.
+    pub const fn new_from_u64s(values: [u64; Self::LEN]) -> Self {
+        let mut limbs = Self::ALL_ZEROS.0;
+        let mut i = 0;
+        while i < Self::LEN {
+            limbs[i] = BFieldElement::new(values[i]);
+            i += 1;
+        }
+        Self(limbs)
+    }
+
+    /// The canonical [values](BFieldElement::value) of the digest's elements.
+    pub const fn values_as_u64s(&self) -> [u64; Self::LEN] {
+        let mut values = [0; Self::LEN];
+        let mut i = 0;
+        while i < Self::LEN {
+            values[i] = self.0[i].value();
+            i += 1;
+        }
+        values
+    }
+
+    /// An iterator over the digest's elements, in the same order as [`Self::values`].
+    pub fn iter(&self) -> std::slice::Iter<'_, BFieldElement> {
+        self.0.iter()
+    }
+
+    /// Creates a new digest from a slice of exactly [`Self::LEN`] elements, for hot paths
+    /// where the length is known. Otherwise, prefer the [`TryFrom`] implementation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice does not have exactly [`Self::LEN`] elements.
+    pub fn from_slice_unchecked(elements: &[BFieldElement]) -> Self {
+        match Self::try_from(elements) {
+            Ok(digest) => digest,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// The canonical byte encoding: the limbs in order, each limb's canonical
+    /// [value](BFieldElement::value) as 8 bytes in little-endian order. This encoding is
+    /// stable and suitable for persistence; the [hex encoding](std::fmt::LowerHex) is the
+    /// same bytes as hex digits.
+    pub const fn to_bytes(self) -> [u8; Self::BYTES] {
+        let mut bytes = [0; Self::BYTES];
+        let mut i = 0;
+        while i < Self::LEN {
+            let limb_bytes = self.0[i].to_le_bytes();
+            let mut j = 0;
+            while j < BFieldElement::BYTES {
+                bytes[i * BFieldElement::BYTES + j] = limb_bytes[j];
+                j += 1;
+            }
+            i += 1;
+        }
+        bytes
+    }
+
+    /// Decode the canonical [byte encoding](Self::to_bytes). Limbs that are not smaller than
+    /// [`BFieldElement::P`] are rejected rather than reduced.
+    pub const fn from_bytes(bytes: &[u8; Self::BYTES]) -> Result<Self, DigestBytesError> {
+        let mut limbs = Self::ALL_ZEROS.0;
+        let mut i = 0;
+        while i < Self::LEN {
+            let mut limb_bytes = [0; BFieldElement::BYTES];
+            let mut j = 0;
+            while j < BFieldElement::BYTES {
+                limb_bytes[j] = bytes[i * BFieldElement::BYTES + j];
+                j += 1;
+            }
+            limbs[i] = match BFieldElement::from_le_bytes(&limb_bytes) {
+                Ok(limb) => limb,
+                Err(NotCanonicalError(value)) => {
+                    return Err(DigestBytesError::NotCanonical { limb: i, value });
+                }
+            };
+            i += 1;
+        }
+        Ok(Self(limbs))
+    }
+}
+
+/// Decodes the canonical [byte encoding](Digest::to_bytes), checking the length.
+impl TryFrom<&[u8]> for Digest {
+    type Error = DigestBytesError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let Ok(bytes) = bytes.try_into() else {
+            return Err(DigestBytesError::InvalidLength(bytes.len()));
+        };
+        Self::from_bytes(bytes)
+    }
+}
+
+/// The canonical [byte encoding](Digest::to_bytes).
+impl From<Digest> for [u8; Digest::BYTES] {
+    fn from(digest: Digest) -> Self {
+        digest.to_bytes()
+    }
+}
+
+/// The canonical [byte encoding](Digest::to_bytes).
+impl From<Digest> for Vec<u8> {
+    fn from(digest: Digest) -> Self {
+        digest.to_bytes().to_vec()
+    }
+}
+
+/// The error returned when decoding a [`Digest`] from bytes fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestBytesError {
+    /// The input does not consist of exactly 40 bytes, but of the given number of bytes.
+    InvalidLength(usize),
+
+    /// The limb with the given index encodes an integer that is not smaller than
+    /// [`BFieldElement::P`].
+    NotCanonical { limb: usize, value: u64 },
+}
+
+impl std::fmt::Display for DigestBytesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "digest must have {} bytes, got {len}", Digest::BYTES)
+            }
+            Self::NotCanonical { limb, value } => write!(
+                f,
+                "digest limb {limb} is {value}, must be smaller than {}",
+                BFieldElement::P
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DigestBytesError {}
+
+/// Validates that every limb is a canonical [value](BFieldElement::value), _i.e._, smaller
+/// than [`BFieldElement::P`]. Non-canonical limbs are rejected rather than reduced.
+impl TryFrom<[u64; Digest::LEN]> for Digest {
+    type Error = TryFromLimbsError;
+
+    fn try_from(values: [u64; Digest::LEN]) -> Result<Self, Self::Error> {
+        let mut limbs = Self::ALL_ZEROS.0;
+        for (limb, (element, value)) in limbs.iter_mut().zip(values).enumerate() {
+            *element = BFieldElement::try_from(value)
+                .map_err(|_| TryFromLimbsError::NotCanonical { limb, value })?;
+        }
+        Ok(Self(limbs))
+    }
+}
+
+/// Validates the length and, like for arrays, that every limb is canonical.
+impl TryFrom<Vec<u64>> for Digest {
+    type Error = TryFromLimbsError;
+
+    fn try_from(values: Vec<u64>) -> Result<Self, Self::Error> {
+        let values: [u64; Self::LEN] = values
+            .try_into()
+            .map_err(|values: Vec<_>| TryFromLimbsError::InvalidLength(values.len()))?;
+        Self::try_from(values)
+    }
+}
+
+impl Digest {
+    /// The canonical [values](BFieldElement::value) of the limbs as ten 32-bit words: the
+    /// limbs in order, each [split](BFieldElement::split_lo_hi) into its low and then its
+    /// high 32 bits.
+    pub const fn to_u32_limbs(&self) -> [u32; 2 * Self::LEN] {
+        let mut words = [0; 2 * Self::LEN];
+        let mut i = 0;
+        while i < Self::LEN {
+            let (lo, hi) = self.0[i].split_lo_hi();
+            words[2 * i] = lo;
+            words[2 * i + 1] = hi;
+            i += 1;
+        }
+        words
+    }
+
+    /// The inverse of [`Self::to_u32_limbs`]. Limbs that are not smaller than
+    /// [`BFieldElement::P`] are rejected rather than reduced.
+    pub fn from_u32_limbs(words: [u32; 2 * Self::LEN]) -> Result<Self, TryFromLimbsError> {
+        let values = std::array::from_fn(|i| {
+            let [lo, hi] = [words[2 * i], words[2 * i + 1]];
+            (u64::from(hi) << 32) | u64::from(lo)
+        });
+        Self::try_from(values)
+    }
+}
+
+/// The canonical [values](BFieldElement::value) of the limbs.
+impl From<Digest> for [u64; Digest::LEN] {
+    fn from(digest: Digest) -> Self {
+        digest.values_as_u64s()
+    }
+}
+
+/// The digest as one integer, composed from the canonical [values](BFieldElement::value) of
+/// its elements in radix 2^64, least significant limb first. This agrees with the
+/// [ordering](Ord) of digests.
+#[cfg(feature = "num-bigint")]
+impl From<Digest> for num_bigint::BigUint {
+    fn from(digest: Digest) -> Self {
+        Self::from_bytes_le(&digest.to_bytes())
+    }
+}
+
+/// Decomposes the integer in radix 2^64, least significant limb first. Integers of 2^320 or
+/// more have too many limbs, and every limb must be canonical, _i.e._, smaller than
+/// [`BFieldElement::P`].
+#[cfg(feature = "num-bigint")]
+impl TryFrom<num_bigint::BigUint> for Digest {
+    type Error = TryFromLimbsError;
+
+    fn try_from(value: num_bigint::BigUint) -> Result<Self, Self::Error> {
+        let mut limbs = value.to_u64_digits();
+        if limbs.len() > Self::LEN {
+            return Err(TryFromLimbsError::InvalidLength(limbs.len()));
+        }
+        limbs.resize(Self::LEN, 0);
+        Self::try_from(limbs)
+    }
+}
+
+/// The error returned when converting plain `u64` limbs into a [`Digest`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryFromLimbsError {
+    /// The input does not consist of exactly [`Digest::LEN`] limbs, but of the given number
+    /// of limbs.
+    InvalidLength(usize),
+
+    /// The limb with the given index is not smaller than [`BFieldElement::P`].
+    NotCanonical { limb: usize, value: u64 },
+}
+
+impl std::fmt::Display for TryFromLimbsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "digest must have {} limbs, got {len}", Digest::LEN)
+            }
+            Self::NotCanonical { limb, value } => write!(
+                f,
+                "digest limb {limb} is {value}, must be smaller than {}",
+                BFieldElement::P
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TryFromLimbsError {}
+
+impl TryFrom<&[BFieldElement]> for Digest {
+    type Error = TryFromDigestError;
+
+    fn try_from(elements: &[BFieldElement]) -> Result<Self, Self::Error> {
+        let elements = elements
+            .try_into()
+            .map_err(|_| TryFromDigestError(elements.len()))?;
+        Ok(Self(elements))
+    }
+}
+
+impl TryFrom<Vec<BFieldElement>> for Digest {
+    type Error = TryFromDigestError;
+
+    fn try_from(elements: Vec<BFieldElement>) -> Result<Self, Self::Error> {
+        Self::try_from(elements.as_slice())
+    }
+}
+
+/// The error returned when converting a sequence of [elements](BFieldElement) into a
+/// [`Digest`] fails. Holds the length of the sequence, which is not [`Digest::LEN`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromDigestError(pub usize);
+
+impl std::fmt::Display for TryFromDigestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "digest must have {} elements, got {}",
+            Digest::LEN,
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for TryFromDigestError {}
+
+impl Digest {
+    /// Write the canonical hex encoding: the limbs in order, each limb's canonical
+    /// [value](BFieldElement::value) as 8 bytes in little-endian order, and each byte as two
+    /// hex digits. The alternate flag `#` adds the prefix `0x`.
+    fn fmt_hex(&self, f: &mut std::fmt::Formatter<'_>, uppercase: bool) -> std::fmt::Result {
+        use std::fmt::Write;
+
+        let mut hex = String::with_capacity(2 + 16 * Self::LEN);
+        if f.alternate() {
+            hex.push_str("0x");
+        }
+        for byte in self.to_bytes() {
+            if uppercase {
+                write!(hex, "{byte:02X}")?;
+            } else {
+                write!(hex, "{byte:02x}")?;
+            }
+        }
+
+        f.pad(&hex)
+    }
+}
+
+impl Digest {
+    /// Parse the canonical hex encoding, as produced by the [`Display`](std::fmt::Display)
+    /// implementation. The prefix `0x` is optional, and hex digits are case-insensitive.
+    ///
+    /// For digest literals, see the [`digest!`](crate::digest) macro.
+    pub const fn from_hex(hex: &str) -> Result<Self, ParseDigestError> {
+        let digits = match hex.as_bytes() {
+            [b'0', b'x' | b'X', digits @ ..] => digits,
+            digits => digits,
+        };
+
+        let mut i = 0;
+        while i < digits.len() {
+            if !digits[i].is_ascii_hexdigit() {
+                return Err(ParseDigestError::InvalidCharacters);
+            }
+            i += 1;
+        }
+        if digits.len() != 16 * Self::LEN {
+            return Err(ParseDigestError::InvalidLength(digits.len()));
+        }
+
+        let mut limbs = Self::ALL_ZEROS.0;
+        let mut limb = 0;
+        while limb < Self::LEN {
+            let mut value = 0;
+            let mut byte = 0;
+            while byte < 8 {
+                let position = 16 * limb + 2 * byte;
+                let high = hex_digit_value(digits[position]);
+                let low = hex_digit_value(digits[position + 1]);
+                value |= (((high << 4) | low) as u64) << (8 * byte);
+                byte += 1;
+            }
+
+            limbs[limb] = match BFieldElement::from_le_bytes(&value.to_le_bytes()) {
+                Ok(element) => element,
+                Err(NotCanonicalError(value)) => {
+                    return Err(ParseDigestError::NotCanonical { limb, value });
+                }
+            };
+            limb += 1;
+        }
+
+        Ok(Self(limbs))
+    }
+
+    /// Implementation detail of the [`digest!`](crate::digest) macro.
+    #[doc(hidden)]
+    pub const fn __from_hex_or_panic(hex: &str) -> Self {
+        match Self::from_hex(hex) {
+            Ok(digest) => digest,
+            Err(ParseDigestError::InvalidLength(_)) => {
+                panic!("digest literal must have exactly 80 hex digits")
+            }
+            Err(ParseDigestError::InvalidCharacters) => {
+                panic!("digest literal must only contain hex digits")
+            }
+            Err(ParseDigestError::NotCanonical { .. }) => {
+                panic!("digest literal has a limb that is not smaller than P")
+            }
+        }
+    }
+
+    /// Implementation detail of the [`digest!`](crate::digest) macro.
+    #[doc(hidden)]
+    pub const fn __from_u64s_or_panic(values: [u64; Self::LEN]) -> Self {
+        let mut limbs = Self::ALL_ZEROS.0;
+        let mut i = 0;
+        while i < Self::LEN {
+            limbs[i] = match BFieldElement::from_le_bytes(&values[i].to_le_bytes()) {
+                Ok(element) => element,
+                Err(_) => panic!("digest literal has a limb that is not smaller than P"),
+            };
+            i += 1;
+        }
+        Self(limbs)
+    }
+}
+
+/// The value of an ASCII hex digit.
+const fn hex_digit_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        b'A'..=b'F' => digit - b'A' + 10,
+        _ => panic!("not a hex digit"),
+    }
+}
+
+/// Parses the canonical hex encoding. See [`Digest::from_hex`].
+impl std::str::FromStr for Digest {
+    type Err = ParseDigestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+/// The error returned when parsing a [`Digest`] from a string fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseDigestError {
+    /// The string, without prefix, does not consist of exactly 80 hex digits, but of the
+    /// given number of digits.
+    InvalidLength(usize),
+
+    /// The string contains characters that are not hex digits.
+    InvalidCharacters,
+
+    /// The limb with the given index encodes an integer that is not smaller than
+    /// [`BFieldElement::P`].
+    NotCanonical { limb: usize, value: u64 },
+}
+
+impl std::fmt::Display for ParseDigestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(
+                    f,
+                    "digest must have {} hex digits, got {len}",
+                    16 * Digest::LEN
+                )
+            }
+            Self::InvalidCharacters => write!(f, "invalid characters in digest"),
+            Self::NotCanonical { limb, value } => write!(
+                f,
+                "digest limb {limb} is {value}, must be smaller than {}",
+                BFieldElement::P
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseDigestError {}
+
+impl Digest {
+    /// An abbreviation of the [hex encoding](std::fmt::LowerHex) for log lines: the first 8
+    /// and the last 8 hex digits, separated by `…` (U+2026), for example
+    /// `850ca3c5…b80e3b31`. This format is stable.
+    ///
+    /// See [`Self::to_abbreviated_string_with_precision`] for more digits.
+    pub fn to_abbreviated_string(&self) -> String {
+        self.to_abbreviated_string_with_precision(8)
+    }
+
+    /// Like [`Self::to_abbreviated_string`], but with the first and the last `num_digits`
+    /// hex digits. If this would show all 80 digits or more, the full hex encoding is
+    /// returned instead.
+    pub fn to_abbreviated_string_with_precision(&self, num_digits: usize) -> String {
+        let hex = self.to_string();
+        if 2 * num_digits >= hex.len() {
+            return hex;
+        }
+        format!("{}…{}", &hex[..num_digits], &hex[hex.len() - num_digits..])
+    }
+}
+
+/// The 80 hex digits of the canonical encoding, lowercase. See [`LowerHex`](std::fmt::LowerHex).
+impl std::fmt::Display for Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_hex(f, false)
+    }
+}
+
+/// The canonical hex encoding: the limbs in order, each limb's canonical value as 8 bytes in
+/// little-endian order, and each byte as two hex digits, for a total of 80 digits.
+///
+/// The alternate flag `#` adds the prefix `0x`. Width, fill, and alignment behave like for
+/// strings. So does precision, which truncates the output.
+impl std::fmt::LowerHex for Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_hex(f, false)
+    }
+}
+
+/// Like [`LowerHex`](std::fmt::LowerHex), but with uppercase hex digits.
+impl std::fmt::UpperHex for Digest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_hex(f, true)
+    }
+}
+
+/// Serializes the five limbs in order, each like a [`BFieldElement`], which coincides with
+/// the canonical [byte encoding](Digest::to_bytes).
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Digest {
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+/// Deserializes five canonical limbs. Limbs that are not smaller than [`BFieldElement::P`]
+/// are rejected rather than reduced.
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Digest {
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        borsh::BorshDeserialize::deserialize_reader(reader).map(Self)
+    }
+}
+
+/// The [archived](rkyv::Archive) form of a [`Digest`]: its five
+/// [archived elements](crate::ArchivedBFieldElement) in order.
+///
+/// [Validation](rkyv::bytecheck::CheckBytes) rejects limbs that are not smaller than
+/// [`BFieldElement::P`], so archives from untrusted sources can be accessed safely.
+#[cfg(feature = "rkyv")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct ArchivedDigest([ArchivedBFieldElement; Digest::LEN]);
+
+#[cfg(feature = "rkyv")]
+impl ArchivedDigest {
+    /// The archived elements.
+    pub fn values(&self) -> &[ArchivedBFieldElement; Digest::LEN] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl From<&ArchivedDigest> for Digest {
+    fn from(archived: &ArchivedDigest) -> Self {
+        Self(archived.0.map(BFieldElement::from))
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl PartialEq<Digest> for ArchivedDigest {
+    fn eq(&self, other: &Digest) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl rkyv::Archive for Digest {
+    type Archived = ArchivedDigest;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
+        let archived = ArchivedDigest(self.0.map(ArchivedBFieldElement::new));
+        // SAFETY: the caller guarantees that `out` is valid for writes and properly aligned
+        unsafe { out.write(archived) };
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<S: rkyv::Fallible + ?Sized> rkyv::Serialize<S> for Digest {
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<D: rkyv::Fallible + ?Sized> rkyv::Deserialize<Digest, D> for ArchivedDigest {
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<Digest, D::Error> {
+        Ok(Digest::from(self))
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<C: ?Sized> rkyv::bytecheck::CheckBytes<C> for ArchivedDigest {
+    type Error = TryFromLimbsError;
+
+    unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
+        // SAFETY: the caller guarantees that `value` is aligned and points to enough bytes.
+        // Every bit pattern is a valid array of `Archived<u64>`.
+        let archived = unsafe { &*value };
+        for (limb, element) in archived.0.iter().enumerate() {
+            let value = element.value();
+            if value >= BFieldElement::P {
+                return Err(TryFromLimbsError::NotCanonical { limb, value });
+            }
+        }
+        Ok(archived)
+    }
+}
+
+/// Human-readable formats like JSON get the canonical hex string, as produced by the
+/// [`Display`](std::fmt::Display) implementation. Binary formats get the canonical
+/// [values](BFieldElement::value) of the five limbs as a fixed-length array.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Digest {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            self.values_as_u64s().serialize(serializer)
+        }
+    }
+}
+
+/// Limbs that are not smaller than [`BFieldElement::P`] are rejected rather than reduced.
+///
+/// For migration purposes, human-readable formats accept both the hex string and the
+/// array of five limbs.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Digest {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return deserializer.deserialize_any(DigestVisitor);
+        }
+
+        let values = <[u64; Self::LEN]>::deserialize(deserializer)?;
+        let mut limbs = [BFieldElement::default(); Self::LEN];
+        for (limb, value) in limbs.iter_mut().zip(values) {
+            *limb = BFieldElement::try_from(value).map_err(serde::de::Error::custom)?;
+        }
+        Ok(Self(limbs))
+    }
+}
+
+#[cfg(feature = "serde")]
+struct DigestVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for DigestVisitor {
+    type Value = Digest;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "a hex-encoded digest or an array of {} limbs",
+            Digest::LEN
+        )
+    }
+
+    fn visit_str<E: serde::de::Error>(self, hex: &str) -> Result<Self::Value, E> {
+        Digest::from_hex(hex).map_err(E::custom)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut limbs = [BFieldElement::default(); Digest::LEN];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let Some(element) = seq.next_element()? else {
+                return Err(serde::de::Error::invalid_length(i, &self));
+            };
+            *limb = element;
+        }
+        if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+            return Err(serde::de::Error::invalid_length(Digest::LEN + 1, &self));
+        }
+
+        Ok(Digest(limbs))
+    }
+}
+
+/// Samples every element uniformly at random, see [`BFieldElement`]'s implementation.
+#[cfg(feature = "rand")]
+impl rand::distributions::Distribution<Digest> for rand::distributions::Standard {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Digest {
+        Digest::new(rng.r#gen())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl Digest {
+    /// Sample a digest uniformly at random. Shorthand for `rng.gen::<Digest>()`.
+    pub fn random<R: rand::Rng + ?Sized>(rng: &mut R) -> Self {
+        rng.r#gen()
+    }
+}
+
+/// Uniformly distributed digests, composed from the [`BFieldElement`] strategy. Elements
+/// shrink individually toward zero, _i.e._, digests shrink toward [`Digest::ALL_ZEROS`].
+#[cfg(feature = "proptest")]
+impl proptest::arbitrary::Arbitrary for Digest {
+    type Parameters = ();
+    type Strategy = proptest::strategy::Map<
+        proptest::array::UniformArrayStrategy<
+            <BFieldElement as proptest::arbitrary::Arbitrary>::Strategy,
+            [BFieldElement; Digest::LEN],
+        >,
+        fn([BFieldElement; Digest::LEN]) -> Self,
+    >;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+
+        proptest::array::uniform5(proptest::arbitrary::any::<BFieldElement>()).prop_map(Self::new)
+    }
+}
+
+/// A [`proptest`] strategy for digests that commonly trigger edge cases: the digest of all
+/// zeros, the digest of all P-1, and digests with exactly one non-zero limb.
+#[cfg(feature = "proptest")]
+pub fn digest_corner_cases() -> impl proptest::strategy::Strategy<Value = Digest> {
+    use proptest::prelude::*;
+
+    let all_max = Digest::new_from_u64s([BFieldElement::P - 1; Digest::LEN]);
+    let single_non_zero_limb = (0..Digest::LEN, 1..BFieldElement::P).prop_map(|(limb, value)| {
+        let mut digest = Digest::ALL_ZEROS;
+        digest.0[limb] = BFieldElement::new(value);
+        digest
+    });
+
+    prop_oneof![Just(Digest::ALL_ZEROS), Just(all_max), single_non_zero_limb]
+}
+
+/// Element-wise arbitrary digests. Shrinking shrinks one element at a time.
+#[cfg(feature = "quickcheck")]
+impl quickcheck::Arbitrary for Digest {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self::new(std::array::from_fn(|_| BFieldElement::arbitrary(g)))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let digest = *self;
+        let shrunk = (0..Self::LEN).flat_map(move |i| {
+            digest.0[i].shrink().map(move |element| {
+                let mut shrunk = digest;
+                shrunk.0[i] = element;
+                shrunk
+            })
+        });
+        Box::new(shrunk)
+    }
+}
+
+/// The error returned by [`Digest::verify`] if the digests differ.
+#[cfg(feature = "subtle")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationError;
+
+#[cfg(feature = "subtle")]
+impl std::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "digest verification failed")
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl std::error::Error for VerificationError {}
+
+/// See [`Digest::ct_eq`].
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for Digest {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        Digest::ct_eq(self, other)
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl Digest {
+    /// Compare `self` and `other` in constant time. All limbs are compared, regardless of
+    /// where the digests differ.
+    ///
+    /// Use this or [`Self::verify`] instead of `==` whenever a digest serves as an
+    /// authenticator, for example a MAC tag or a capability token, as [`PartialEq`] leaks the
+    /// position of the first differing limb.
+    pub fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        use subtle::ConstantTimeEq;
+
+        self.0
+            .iter()
+            .zip(&other.0)
+            .fold(subtle::Choice::from(1), |acc, (a, b)| acc & a.ct_eq(b))
+    }
+
+    /// Check that `self` equals `other` in [constant time](Self::ct_eq).
+    pub fn verify(&self, other: &Self) -> Result<(), VerificationError> {
+        if bool::from(self.ct_eq(other)) {
+            Ok(())
+        } else {
+            Err(VerificationError)
+        }
+    }
+}
+
+/// The [`Default`] is [`Digest::ALL_ZEROS`], whose elements are all-zero words, so it is a
+/// proper zeroization target. This implies [`zeroize::Zeroize`] for `Digest` as well as for
+/// arrays and slices of it.
+#[cfg(feature = "zeroize")]
+impl zeroize::DefaultIsZeroes for Digest {}
+
+/// A [`Digest`] derived from secret material, for example the output of a key derivation or
+/// a keyed hash. The digest is [zeroized](zeroize::Zeroize) when the wrapper is dropped, and
+/// its [`Debug`](std::fmt::Debug) representation never reveals the limbs.
+///
+/// There is no [`PartialEq`] because `==` would stop at the first differing limb. With
+/// feature `subtle`, secret digests are compared in constant time with
+/// [`ConstantTimeEq`](subtle::ConstantTimeEq).
+#[cfg(feature = "zeroize")]
+#[derive(Clone, Default)]
+pub struct SecretDigest(Digest);
+
+#[cfg(feature = "zeroize")]
+impl SecretDigest {
+    pub const fn new(digest: Digest) -> Self {
+        Self(digest)
+    }
+
+    /// The secret digest. Copies of it are not zeroized automatically.
+    pub const fn expose_secret(&self) -> &Digest {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl From<Digest> for SecretDigest {
+    fn from(digest: Digest) -> Self {
+        Self(digest)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl std::fmt::Debug for SecretDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretDigest(<redacted>)")
+    }
+}
+
+/// See [`Digest::ct_eq`].
+#[cfg(all(feature = "zeroize", feature = "subtle"))]
+impl subtle::ConstantTimeEq for SecretDigest {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretDigest {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretDigest {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretDigest {}
+
+#[cfg(feature = "base58")]
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[cfg(feature = "base58")]
+const BASE58_CHECKSUM_LEN: usize = 4;
+
+#[cfg(feature = "base58")]
+impl Digest {
+    /// The Base58Check encoding: the version byte, the canonical
+    /// [byte encoding](Self::to_bytes), and a 4-byte checksum, all encoded with the Bitcoin
+    /// Base58 alphabet.
+    ///
+    /// The checksum does not use SHA-256. Instead, it consists of the first four bytes of the
+    /// byte encoding of Tip5 applied twice: first with
+    /// [`hash_varlen`](crate::Tip5::hash_varlen) to the version byte and the 40 bytes of the
+    /// digest, one element per byte, and then to the elements of the result.
+    pub fn to_base58check(&self, version: u8) -> String {
+        let mut payload = Vec::with_capacity(1 + Self::BYTES + BASE58_CHECKSUM_LEN);
+        payload.push(version);
+        payload.extend_from_slice(&self.to_bytes());
+        payload.extend_from_slice(&base58check_checksum(&payload));
+        base58_encode(&payload)
+    }
+
+    /// Decode the [Base58Check encoding](Self::to_base58check), checking the checksum and
+    /// that the version byte is `expected_version`.
+    pub fn from_base58check(s: &str, expected_version: u8) -> Result<Self, Base58CheckError> {
+        let payload = base58_decode(s).ok_or(Base58CheckError::InvalidCharacters)?;
+        if payload.len() != 1 + Self::BYTES + BASE58_CHECKSUM_LEN {
+            return Err(Base58CheckError::InvalidLength(payload.len()));
+        }
+
+        let (data, checksum) = payload.split_at(1 + Self::BYTES);
+        if base58check_checksum(data) != checksum {
+            return Err(Base58CheckError::InvalidChecksum);
+        }
+
+        let (&version, bytes) = data.split_first().unwrap();
+        if version != expected_version {
+            return Err(Base58CheckError::WrongVersion {
+                expected: expected_version,
+                found: version,
+            });
+        }
+
+        match Self::from_bytes(bytes.try_into().unwrap()) {
+            Ok(digest) => Ok(digest),
+            Err(DigestBytesError::NotCanonical { limb, value }) => {
+                Err(Base58CheckError::NotCanonical { limb, value })
+            }
+            Err(DigestBytesError::InvalidLength(_)) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "base58")]
+fn base58check_checksum(data: &[u8]) -> [u8; BASE58_CHECKSUM_LEN] {
+    let elements = data
+        .iter()
+        .map(|&byte| BFieldElement::from(byte))
+        .collect::<Vec<_>>();
+    let first = crate::Tip5::hash_varlen(&elements);
+    let second = crate::Tip5::hash_varlen(&first.values());
+
+    let mut checksum = [0; BASE58_CHECKSUM_LEN];
+    checksum.copy_from_slice(&second.to_bytes()[..BASE58_CHECKSUM_LEN]);
+    checksum
+}
+
+/// Base58-encode the bytes, interpreted as a big-endian integer. Every leading zero byte
+/// becomes a leading `1`.
+#[cfg(feature = "base58")]
+fn base58_encode(bytes: &[u8]) -> String {
+    let num_leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+
+    // little-endian base-58 digits
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in bytes {
+        let mut carry = u32::from(byte);
+        for digit in &mut digits {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let leading_ones = std::iter::repeat_n(BASE58_ALPHABET[0], num_leading_zeros);
+    let digits = digits
+        .iter()
+        .rev()
+        .map(|&digit| BASE58_ALPHABET[digit as usize]);
+    leading_ones.chain(digits).map(char::from).collect()
+}
+
+/// The inverse of [`base58_encode`], or [`None`] if `s` contains characters outside the
+/// Base58 alphabet.
+#[cfg(feature = "base58")]
+fn base58_decode(s: &str) -> Option<Vec<u8>> {
+    let num_leading_ones = s.bytes().take_while(|&c| c == BASE58_ALPHABET[0]).count();
+
+    // little-endian bytes
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len());
+    for c in s.bytes() {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in &mut bytes {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let leading_zeros = std::iter::repeat_n(0, num_leading_ones);
+    Some(leading_zeros.chain(bytes.into_iter().rev()).collect())
+}
+
+/// The error returned by [`Digest::from_base58check`].
+#[cfg(feature = "base58")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base58CheckError {
+    /// The input contains characters outside the Base58 alphabet.
+    InvalidCharacters,
+
+    /// The input does not decode to exactly 45 bytes, but to the given number of bytes.
+    InvalidLength(usize),
+
+    /// The checksum does not match.
+    InvalidChecksum,
+
+    /// The version byte is not the expected one.
+    WrongVersion { expected: u8, found: u8 },
+
+    /// The limb with the given index encodes an integer that is not smaller than
+    /// [`BFieldElement::P`].
+    NotCanonical { limb: usize, value: u64 },
+}
+
+#[cfg(feature = "base58")]
+impl std::fmt::Display for Base58CheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCharacters => {
+                write!(f, "base58check digest must only contain base58 characters")
+            }
+            Self::InvalidLength(len) => write!(
+                f,
+                "base58check digest must decode to {} bytes, got {len}",
+                1 + Digest::BYTES + BASE58_CHECKSUM_LEN
+            ),
+            Self::InvalidChecksum => write!(f, "base58check digest has an invalid checksum"),
+            Self::WrongVersion { expected, found } => {
+                write!(
+                    f,
+                    "base58check digest has version {found}, expected {expected}"
+                )
+            }
+            Self::NotCanonical { limb, value } => write!(
+                f,
+                "digest limb {limb} is {value}, must be smaller than {}",
+                BFieldElement::P
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "base58")]
+impl std::error::Error for Base58CheckError {}
+
+#[cfg(feature = "encodings")]
+const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[cfg(feature = "encodings")]
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+#[cfg(feature = "encodings")]
+impl Digest {
+    /// The unpadded RFC 4648 base64url encoding of the canonical
+    /// [byte encoding](Self::to_bytes), consisting of 54 characters.
+    pub fn to_base64url(&self) -> String {
+        encode_bits(&self.to_bytes(), BASE64URL_ALPHABET, 6)
+    }
+
+    /// Like [`Self::to_base64url`], but padded with `==` to 56 characters.
+    pub fn to_base64url_padded(&self) -> String {
+        self.to_base64url() + "=="
+    }
+
+    /// Decode the [unpadded base64url encoding](Self::to_base64url). Padding is rejected, as
+    /// are non-zero unused bits in the last character.
+    pub fn from_base64url(s: &str) -> Result<Self, DecodeDigestError> {
+        if s.contains('=') {
+            return Err(DecodeDigestError::InvalidPadding);
+        }
+        let bytes = decode_bits(s, BASE64URL_ALPHABET, 6)?;
+        Self::from_decoded_bytes(&bytes)
+    }
+
+    /// Decode the [padded base64url encoding](Self::to_base64url_padded). Missing or
+    /// superfluous padding is rejected.
+    pub fn from_base64url_padded(s: &str) -> Result<Self, DecodeDigestError> {
+        match s.strip_suffix("==") {
+            Some(unpadded) if !unpadded.ends_with('=') => Self::from_base64url(unpadded),
+            _ => Err(DecodeDigestError::InvalidPadding),
+        }
+    }
+
+    /// The RFC 4648 base32 encoding of the canonical [byte encoding](Self::to_bytes),
+    /// consisting of 64 upper-case characters. Since 40 bytes are a multiple of 5 bytes,
+    /// the encoding never needs padding, and the padded and unpadded variants agree.
+    pub fn to_base32(&self) -> String {
+        encode_bits(&self.to_bytes(), BASE32_ALPHABET, 5)
+    }
+
+    /// Decode the [base32 encoding](Self::to_base32). Lower-case characters and padding are
+    /// rejected.
+    pub fn from_base32(s: &str) -> Result<Self, DecodeDigestError> {
+        if s.contains('=') {
+            return Err(DecodeDigestError::InvalidPadding);
+        }
+        let bytes = decode_bits(s, BASE32_ALPHABET, 5)?;
+        Self::from_decoded_bytes(&bytes)
+    }
+
+    fn from_decoded_bytes(bytes: &[u8]) -> Result<Self, DecodeDigestError> {
+        match Self::try_from(bytes) {
+            Ok(digest) => Ok(digest),
+            Err(DigestBytesError::InvalidLength(_)) => unreachable!(),
+            Err(DigestBytesError::NotCanonical { limb, value }) => {
+                Err(DecodeDigestError::NotCanonical { limb, value })
+            }
+        }
+    }
+}
+
+/// Encode the bytes, most significant bit first, with `bits_per_char` bits per character.
+/// The last character is filled up with zero bits.
+#[cfg(feature = "encodings")]
+fn encode_bits(bytes: &[u8], alphabet: &[u8], bits_per_char: u32) -> String {
+    let mask = (1 << bits_per_char) - 1;
+    let mut encoded = String::with_capacity((8 * bytes.len()).div_ceil(bits_per_char as usize));
+    let mut buffer = 0_u32;
+    let mut num_buffered_bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        num_buffered_bits += 8;
+        while num_buffered_bits >= bits_per_char {
+            num_buffered_bits -= bits_per_char;
+            encoded.push(char::from(
+                alphabet[((buffer >> num_buffered_bits) & mask) as usize],
+            ));
+        }
+    }
+    if num_buffered_bits > 0 {
+        let index = (buffer << (bits_per_char - num_buffered_bits)) & mask;
+        encoded.push(char::from(alphabet[index as usize]));
+    }
+    encoded
+}
+
+/// The inverse of [`encode_bits`] for inputs encoding [`Digest::BYTES`] bytes.
+#[cfg(feature = "encodings")]
+fn decode_bits(s: &str, alphabet: &[u8], bits_per_char: u32) -> Result<Vec<u8>, DecodeDigestError> {
+    let values = s
+        .bytes()
+        .map(|c| alphabet.iter().position(|&a| a == c))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(DecodeDigestError::InvalidCharacters)?;
+
+    let expected_len = (8 * Digest::BYTES).div_ceil(bits_per_char as usize);
+    if values.len() != expected_len {
+        return Err(DecodeDigestError::InvalidLength(values.len()));
+    }
+
+    let mut bytes = Vec::with_capacity(Digest::BYTES);
+    let mut buffer = 0_u32;
+    let mut num_buffered_bits = 0;
+    for value in values {
+        buffer = (buffer << bits_per_char) | value as u32;
+        num_buffered_bits += bits_per_char;
+        if num_buffered_bits >= 8 {
+            num_buffered_bits -= 8;
+            bytes.push((buffer >> num_buffered_bits) as u8);
+        }
+    }
+    if buffer & ((1 << num_buffered_bits) - 1) != 0 {
+        return Err(DecodeDigestError::InvalidPadding);
+    }
+    Ok(bytes)
+}
+
+/// The error returned when decoding a [`Digest`] from its base64url or base32 encoding
+/// fails.
+#[cfg(feature = "encodings")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeDigestError {
+    /// The string, without padding, does not consist of exactly the number of characters
+    /// needed for 40 bytes, but of the given number of characters.
+    InvalidLength(usize),
+
+    /// The string contains characters outside the alphabet.
+    InvalidCharacters,
+
+    /// The padding is missing or superfluous, or the unused bits of the last character are
+    /// not zero.
+    InvalidPadding,
+
+    /// The limb with the given index encodes an integer that is not smaller than
+    /// [`BFieldElement::P`].
+    NotCanonical { limb: usize, value: u64 },
+}
+
+#[cfg(feature = "encodings")]
+impl std::fmt::Display for DecodeDigestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength(len) => write!(f, "encoded digest has invalid length {len}"),
+            Self::InvalidCharacters => write!(f, "invalid characters in encoded digest"),
+            Self::InvalidPadding => write!(f, "invalid padding in encoded digest"),
+            Self::NotCanonical { limb, value } => write!(
+                f,
+                "digest limb {limb} is {value}, must be smaller than {}",
+                BFieldElement::P
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "encodings")]
+impl std::error::Error for DecodeDigestError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VARLEN_DIGEST_HEX: &str =
+        "850ca3c5e6ee04189bd2bc1fd9342425b097463e0c4d9773543941dc4e9989e8cac66b6cb80e3b31";
+
+    fn varlen_digest() -> Digest {
+        crate::Tip5::hash_varlen(&crate::bfe_array![1, 0])
+    }
+
+    #[test]
+    fn digest_macro_agrees_with_runtime_parsing() {
+        const FROM_HEX: Digest = crate::digest!(
+            "850ca3c5e6ee04189bd2bc1fd9342425b097463e0c4d9773543941dc4e9989e8cac66b6cb80e3b31"
+        );
+        assert_eq!(varlen_digest(), FROM_HEX);
+        assert_eq!(Ok(FROM_HEX), Digest::from_hex(VARLEN_DIGEST_HEX));
+
+        let from_uppercase_hex = crate::digest!(
+            "0X850CA3C5E6EE04189BD2BC1FD9342425B097463E0C4D9773543941DC4E9989E8CAC66B6CB80E3B31"
+        );
+        assert_eq!(FROM_HEX, from_uppercase_hex);
+
+        let largest = crate::digest!(0, 0, 0, 0, 0xffff_ffff_0000_0000);
+        assert_eq!(BFieldElement::new(BFieldElement::P - 1), largest.0[4]);
+    }
+
+    #[test]
+    fn hex_encoding_of_known_digest() {
+        let digest = varlen_digest();
+        assert_eq!(VARLEN_DIGEST_HEX, digest.to_string());
+        assert_eq!(VARLEN_DIGEST_HEX, format!("{digest:x}"));
+        assert_eq!(VARLEN_DIGEST_HEX.to_uppercase(), format!("{digest:X}"));
+        assert_eq!(format!("0x{VARLEN_DIGEST_HEX}"), format!("{digest:#x}"));
+    }
+
+    #[test]
+    fn hex_encoding_uses_little_endian_limbs_in_order() {
+        let digest = Digest::new_from_u64s([1, 0, 0, 0, 0x0102]);
+        let expected = format!(
+            "01{}{}0201{}",
+            "0".repeat(14),
+            "0".repeat(48),
+            "0".repeat(12)
+        );
+        assert_eq!(expected, digest.to_string());
+        assert_eq!(80, digest.to_string().len());
+    }
+
+    #[test]
+    fn parse_known_digest() {
+        let digest = varlen_digest();
+        assert_eq!(Ok(digest), VARLEN_DIGEST_HEX.parse());
+        assert_eq!(
+            Ok(digest),
+            Digest::from_hex(&VARLEN_DIGEST_HEX.to_uppercase())
+        );
+        assert_eq!(Ok(digest), format!("0x{VARLEN_DIGEST_HEX}").parse());
+        assert_eq!(Ok(digest), format!("{digest:#X}").parse());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let truncated = &VARLEN_DIGEST_HEX[..79];
+        assert_eq!(
+            Err(ParseDigestError::InvalidLength(79)),
+            Digest::from_hex(truncated)
+        );
+        assert_eq!(
+            Err(ParseDigestError::InvalidLength(0)),
+            Digest::from_hex("")
+        );
+        assert_eq!(
+            Err(ParseDigestError::InvalidLength(0)),
+            Digest::from_hex("0x")
+        );
+        let too_long = format!("{VARLEN_DIGEST_HEX}00");
+        assert_eq!(
+            Err(ParseDigestError::InvalidLength(82)),
+            Digest::from_hex(&too_long)
+        );
+
+        let invalid = ParseDigestError::InvalidCharacters;
+        let with_g = format!("g{}", &VARLEN_DIGEST_HEX[1..]);
+        assert_eq!(Err(invalid), Digest::from_hex(&with_g));
+        assert_eq!(
+            Err(invalid),
+            Digest::from_hex(&format!("+{}", &VARLEN_DIGEST_HEX[1..]))
+        );
+        assert_eq!(
+            Err(invalid),
+            Digest::from_hex(&format!("0x0x{VARLEN_DIGEST_HEX}"))
+        );
+        assert_eq!(
+            Err(invalid),
+            Digest::from_hex(&format!("ü{}", &VARLEN_DIGEST_HEX[2..]))
+        );
+
+        let p_hex = BFieldElement::P
+            .to_le_bytes()
+            .map(|byte| format!("{byte:02x}"))
+            .concat();
+        let with_p = format!(
+            "{}{p_hex}{}",
+            &VARLEN_DIGEST_HEX[..32],
+            &VARLEN_DIGEST_HEX[48..]
+        );
+        let not_canonical = ParseDigestError::NotCanonical {
+            limb: 2,
+            value: BFieldElement::P,
+        };
+        assert_eq!(Err(not_canonical), Digest::from_hex(&with_p));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn hex_encoding_round_trip(values: [u64; Digest::LEN]) {
+            let digest = Digest::new_from_u64s(values);
+            proptest::prop_assert_eq!(Ok(digest), digest.to_string().parse());
+            proptest::prop_assert_eq!(Ok(digest), format!("{digest:#X}").parse());
+        }
+    }
+
+    #[test]
+    fn byte_encoding_uses_little_endian_limbs_in_order() {
+        let digest = crate::digest!(1, 0, 0, 0, 0x0102);
+        let mut expected = [0; Digest::BYTES];
+        expected[0] = 1;
+        expected[32] = 2;
+        expected[33] = 1;
+        assert_eq!(expected, digest.to_bytes());
+        assert_eq!(expected, <[u8; Digest::BYTES]>::from(digest));
+        assert_eq!(expected.to_vec(), Vec::<u8>::from(digest));
+    }
+
+    #[test]
+    fn byte_encoding_agrees_with_hex_encoding() {
+        let digest = varlen_digest();
+        let hex = digest.to_bytes().map(|byte| format!("{byte:02x}")).concat();
+        assert_eq!(VARLEN_DIGEST_HEX, hex);
+    }
+
+    #[test]
+    fn bytes_of_wrong_length_are_rejected() {
+        let bytes = varlen_digest().to_bytes();
+        for len in [0, 39, 41, 80] {
+            let bytes = bytes.iter().copied().cycle().take(len).collect::<Vec<_>>();
+            let err = DigestBytesError::InvalidLength(len);
+            assert_eq!(Err(err), Digest::try_from(bytes.as_slice()));
+        }
+    }
+
+    #[test]
+    fn bytes_with_non_canonical_limb_are_rejected() {
+        let mut bytes = varlen_digest().to_bytes();
+        bytes[24..32].copy_from_slice(&u64::MAX.to_le_bytes());
+        let err = DigestBytesError::NotCanonical {
+            limb: 3,
+            value: u64::MAX,
+        };
+        assert_eq!(Err(err), Digest::from_bytes(&bytes));
+        assert_eq!(Err(err), Digest::try_from(bytes.as_slice()));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn byte_encoding_round_trip(values: [u64; Digest::LEN]) {
+            let digest = Digest::new_from_u64s(values);
+            let bytes = digest.to_bytes();
+            proptest::prop_assert_eq!(Ok(digest), Digest::from_bytes(&bytes));
+            proptest::prop_assert_eq!(Ok(digest), Digest::try_from(bytes.as_slice()));
+        }
+    }
+
+    #[test]
+    fn digest_from_elements_of_correct_length() {
+        let digest = varlen_digest();
+        let elements = digest.values().to_vec();
+        assert_eq!(Ok(digest), Digest::try_from(elements.as_slice()));
+        assert_eq!(digest, Digest::from_slice_unchecked(&elements));
+        assert_eq!(Ok(digest), Digest::try_from(elements));
+    }
+
+    #[test]
+    fn digest_from_elements_of_wrong_length_is_rejected() {
+        for len in [4, 6] {
+            let elements = crate::bfe_vec![42; len];
+            let err = TryFromDigestError(len);
+            assert_eq!(Err(err), Digest::try_from(elements.as_slice()));
+            assert_eq!(Err(err), Digest::try_from(elements));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "digest must have 5 elements, got 6")]
+    fn digest_from_slice_unchecked_panics_on_wrong_length() {
+        Digest::from_slice_unchecked(&crate::bfe_array![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn digest_from_canonical_limbs() {
+        const MAX: u64 = BFieldElement::P - 1;
+        let values = [0, 1, 2, MAX, MAX];
+        let digest = Digest::try_from(values).unwrap();
+        assert_eq!(crate::digest!(0, 1, 2, MAX, MAX), digest);
+        assert_eq!(Ok(digest), Digest::try_from(values.to_vec()));
+        assert_eq!(values, <[u64; Digest::LEN]>::from(digest));
+    }
+
+    #[test]
+    fn digest_from_non_canonical_limbs_is_rejected() {
+        let values = [0, 1, BFieldElement::P, 3, u64::MAX];
+        let err = TryFromLimbsError::NotCanonical {
+            limb: 2,
+            value: BFieldElement::P,
+        };
+        assert_eq!(Err(err), Digest::try_from(values));
+        assert_eq!(Err(err), Digest::try_from(values.to_vec()));
+    }
+
+    #[test]
+    fn digest_from_u64s_in_const_context() {
+        const DIGEST: Digest = Digest::new_from_u64s([1, 2, 3, 4, BFieldElement::P + 5]);
+        const VALUES: [u64; Digest::LEN] = DIGEST.values_as_u64s();
+        assert_eq!(crate::digest!(1, 2, 3, 4, 5), DIGEST);
+        assert_eq!([1, 2, 3, 4, 5], VALUES);
+        assert_eq!(
+            Digest::ALL_ZEROS,
+            Digest::new_from_u64s([BFieldElement::P; 5])
+        );
+    }
+
+    #[test]
+    fn u32_limbs_are_low_then_high_words_of_each_limb() {
+        let digest = crate::digest!(1, 2 << 32, 3, 0xffff_ffff_0000_0000, 5);
+        let expected = [1, 0, 0, 2, 3, 0, 0, 0xffff_ffff, 5, 0];
+        assert_eq!(expected, digest.to_u32_limbs());
+        assert_eq!(Ok(digest), Digest::from_u32_limbs(expected));
+    }
+
+    #[test]
+    fn u32_limbs_of_non_canonical_limb_are_rejected() {
+        let mut words = crate::digest!(1, 2, 3, 4, 5).to_u32_limbs();
+        words[6] = 1;
+        words[7] = 0xffff_ffff;
+        let err = TryFromLimbsError::NotCanonical {
+            limb: 3,
+            value: BFieldElement::P,
+        };
+        assert_eq!(Err(err), Digest::from_u32_limbs(words));
+    }
+
+    #[test]
+    fn digest_from_wrong_number_of_limbs_is_rejected() {
+        for len in [0, 4, 6] {
+            let err = TryFromLimbsError::InvalidLength(len);
+            assert_eq!(Err(err), Digest::try_from(vec![0; len]));
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn limbs_round_trip(values: [u64; Digest::LEN]) {
+            let digest = Digest::new_from_u64s(values);
+            let limbs = <[u64; Digest::LEN]>::from(digest);
+            proptest::prop_assert_eq!(Ok(digest), Digest::try_from(limbs));
+            proptest::prop_assert_eq!(Ok(digest), Digest::from_u32_limbs(digest.to_u32_limbs()));
+        }
+    }
+
+    fn to_biguint(digest: Digest) -> num_bigint::BigUint {
+        num_bigint::BigUint::from_bytes_le(&digest.to_bytes())
+    }
+
+    #[test]
+    fn digests_are_ordered_by_most_significant_limb_first() {
+        let small = crate::digest!(BFieldElement::P - 1, BFieldElement::P - 1, 0, 0, 0);
+        let large = crate::digest!(0, 0, 0, 0, 1);
+        assert!(small < large);
+
+        let last_limb_differs = crate::digest!(1, 2, 3, 4, 6);
+        assert!(crate::digest!(1, 2, 3, 4, 5) < last_limb_differs);
+        assert!(crate::digest!(9, 9, 9, 9, 5) < last_limb_differs);
+
+        let max = crate::digest!(0, 0, 0, 0, BFieldElement::P - 1);
+        assert!(crate::digest!(1, 0, 0, 0, BFieldElement::P - 1) > max);
+        assert_eq!(std::cmp::Ordering::Equal, max.cmp(&max));
+    }
+
+    #[test]
+    fn sort_order_of_known_digests_is_stable() {
+        let mut digests = (0..8)
+            .map(|i| (i, crate::Tip5::hash_varlen(&crate::bfe_array![i])))
+            .collect::<Vec<_>>();
+        digests.sort_by_key(|&(_, digest)| digest);
+        let order = digests.iter().map(|&(i, _)| i).collect::<Vec<_>>();
+        assert_eq!(vec![0, 2, 5, 3, 1, 6, 7, 4], order);
+    }
+
+    #[test]
+    fn leading_zero_bits_span_limbs() {
+        assert_eq!(320, Digest::ALL_ZEROS.leading_zero_bits());
+        assert_eq!(319, crate::digest!(1, 0, 0, 0, 0).leading_zero_bits());
+        assert_eq!(0, crate::digest!(0, 0, 0, 0, 1 << 63).leading_zero_bits());
+        assert_eq!(63, crate::digest!(0, 0, 0, 0, 1).leading_zero_bits());
+        assert_eq!(
+            64,
+            crate::digest!(0, 0, 0, u64::MAX >> 32, 0).leading_zero_bits() - 32
+        );
+        assert_eq!(128 + 61, crate::digest!(7, 0, 4, 0, 0).leading_zero_bits());
+    }
+
+    #[test]
+    fn digest_meets_target_if_at_most_target() {
+        let target = crate::digest!(0, 0, 0, 0, 1 << 48);
+        assert!(target.meets_target(&target));
+        assert!(Digest::ALL_ZEROS.meets_target(&target));
+
+        const MAX: u64 = BFieldElement::P - 1;
+        let below = crate::digest!(MAX, MAX, MAX, MAX, (1 << 48) - 1);
+        assert!(below.meets_target(&target));
+
+        let above = crate::digest!(1, 0, 0, 0, 1 << 48);
+        assert!(!above.meets_target(&target));
+        assert!(target.meets_target(&above));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn ordering_agrees_with_biguint(
+            a: [u64; Digest::LEN],
+            b: [u64; Digest::LEN],
+        ) {
+            let a = Digest::new_from_u64s(a);
+            let b = Digest::new_from_u64s(b);
+            proptest::prop_assert_eq!(to_biguint(a).cmp(&to_biguint(b)), a.cmp(&b));
+            proptest::prop_assert_eq!(to_biguint(a) <= to_biguint(b), a.meets_target(&b));
+        }
+
+        #[test]
+        fn leading_zero_bits_agree_with_biguint(
+            values: [u64; Digest::LEN],
+            shift in 0..64_usize,
+            num_zero_limbs in 0..Digest::LEN,
+        ) {
+            let mut digest = Digest::new_from_u64s(values.map(|value| value >> shift));
+            digest.0[Digest::LEN - num_zero_limbs..].fill(BFieldElement::new(0));
+            let expected = 320 - to_biguint(digest).bits();
+            proptest::prop_assert_eq!(expected, u64::from(digest.leading_zero_bits()));
+        }
+    }
+
+    #[test]
+    fn default_digest_is_all_zeros() {
+        assert_eq!(Digest::ALL_ZEROS, Digest::default());
+        assert_eq!(crate::digest!(0, 0, 0, 0, 0), Digest::ALL_ZEROS);
+        assert_ne!(crate::Tip5::hash_varlen(&[]), Digest::ALL_ZEROS);
+    }
+
+    #[test]
+    fn iteration_order_matches_values() {
+        let mut digest = crate::digest!(1, 2, 3, 4, 5);
+        let values = digest.values();
+        assert_eq!(values.to_vec(), digest.iter().copied().collect::<Vec<_>>());
+        assert_eq!(values.to_vec(), digest.into_iter().collect::<Vec<_>>());
+        assert_eq!(values.as_slice(), digest.as_ref());
+        for (i, element) in (&digest).into_iter().enumerate() {
+            assert_eq!(values[i], *element);
+            assert_eq!(values[i], digest[i]);
+        }
+        assert_eq!(&values[1..4], &digest[1..4]);
+
+        for element in &mut digest {
+            *element += BFieldElement::new(1);
+        }
+        assert_eq!(crate::digest!(2, 3, 4, 5, 6), digest);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds: the len is 5 but the index is 5")]
+    fn out_of_range_index_panics() {
+        let _ = crate::digest!(1, 2, 3, 4, 5)[Digest::LEN];
+    }
+
+    #[test]
+    #[should_panic(expected = "range end index 6 out of range for slice of length 5")]
+    fn out_of_range_range_index_panics() {
+        let _ = &crate::digest!(1, 2, 3, 4, 5)[2..6];
+    }
+
+    #[test]
+    fn hex_encoding_respects_formatter_flags() {
+        let digest = varlen_digest();
+        assert_eq!(format!("{VARLEN_DIGEST_HEX}  "), format!("{digest:82}"));
+        assert_eq!(format!("**{VARLEN_DIGEST_HEX}"), format!("{digest:*>82x}"));
+        assert_eq!(VARLEN_DIGEST_HEX, format!("{digest:10}"));
+        assert_eq!(&VARLEN_DIGEST_HEX[..8], format!("{digest:.8}"));
+        assert_eq!("0x850C", format!("{digest:#.6X}"));
+    }
+
+    #[test]
+    fn abbreviated_string_of_known_digest() {
+        let digest = varlen_digest();
+        assert_eq!("850ca3c5…b80e3b31", digest.to_abbreviated_string());
+        assert_eq!("850c…3b31", digest.to_abbreviated_string_with_precision(4));
+        assert_eq!("…", digest.to_abbreviated_string_with_precision(0));
+        assert_eq!(
+            VARLEN_DIGEST_HEX,
+            digest.to_abbreviated_string_with_precision(40)
+        );
+    }
+
+    #[test]
+    fn digests_sharing_prefix_and_suffix_are_distinguishable_with_higher_precision() {
+        let digest = varlen_digest();
+        let mut other = digest;
+        other.0[1] += BFieldElement::new(1);
+        assert_eq!(
+            digest.to_abbreviated_string(),
+            other.to_abbreviated_string()
+        );
+        assert_ne!(
+            digest.to_abbreviated_string_with_precision(18),
+            other.to_abbreviated_string_with_precision(18)
+        );
+    }
+
+    #[cfg(feature = "base58")]
+    mod base58_tests {
+        use super::*;
+
+        const VERSION: u8 = 0x2a;
+
+        #[test]
+        fn base58check_encoding_of_known_digest() {
+            let encoded = varlen_digest().to_base58check(VERSION);
+            assert_eq!(
+                "2428ubPeKm329WawMwZtz6ADeqw9DCWYHsXxDygVU512NtKbxeL6VYdStb1rLz",
+                encoded
+            );
+            assert_eq!(
+                Ok(varlen_digest()),
+                Digest::from_base58check(&encoded, VERSION)
+            );
+        }
+
+        #[test]
+        fn base58_codec_preserves_leading_zeros() {
+            for bytes in [vec![], vec![0], vec![0, 0, 1], vec![0, 255, 0]] {
+                let encoded = base58_encode(&bytes);
+                assert_eq!(Some(bytes), base58_decode(&encoded));
+            }
+            assert_eq!("11", base58_encode(&[0, 0]));
+            assert_eq!("5Q", base58_encode(&[0xff]));
+        }
+
+        #[test]
+        fn single_character_mutations_are_rejected() {
+            let encoded = varlen_digest().to_base58check(VERSION);
+            for position in 0..encoded.len() {
+                for &replacement in BASE58_ALPHABET {
+                    let mut mutated = encoded.clone().into_bytes();
+                    if mutated[position] == replacement {
+                        continue;
+                    }
+                    mutated[position] = replacement;
+                    let mutated = String::from_utf8(mutated).unwrap();
+                    assert!(Digest::from_base58check(&mutated, VERSION).is_err());
+                }
+            }
+        }
+
+        #[test]
+        fn invalid_characters_are_rejected() {
+            let encoded = varlen_digest().to_base58check(VERSION);
+            for invalid in ["0", "O", "I", "l", "+", " "] {
+                let err = Base58CheckError::InvalidCharacters;
+                let mutated = encoded.replacen(&encoded[3..4], invalid, 1);
+                assert_eq!(Err(err), Digest::from_base58check(&mutated, VERSION));
+            }
+        }
+
+        #[test]
+        fn wrong_length_is_rejected() {
+            let encoded = varlen_digest().to_base58check(VERSION);
+            let err = Base58CheckError::InvalidLength(46);
+            let extended = format!("1{encoded}");
+            assert_eq!(Err(err), Digest::from_base58check(&extended, VERSION));
+        }
+
+        #[test]
+        fn wrong_version_is_rejected() {
+            let encoded = varlen_digest().to_base58check(VERSION);
+            let err = Base58CheckError::WrongVersion {
+                expected: VERSION + 1,
+                found: VERSION,
+            };
+            assert_eq!(Err(err), Digest::from_base58check(&encoded, VERSION + 1));
+        }
+
+        #[test]
+        fn non_canonical_limb_with_valid_checksum_is_rejected() {
+            let mut payload = vec![VERSION];
+            payload.extend_from_slice(&varlen_digest().to_bytes());
+            payload[1..9].copy_from_slice(&u64::MAX.to_le_bytes());
+            let checksum = base58check_checksum(&payload);
+            payload.extend_from_slice(&checksum);
+
+            let err = Base58CheckError::NotCanonical {
+                limb: 0,
+                value: u64::MAX,
+            };
+            let encoded = base58_encode(&payload);
+            assert_eq!(Err(err), Digest::from_base58check(&encoded, VERSION));
+        }
+
+        proptest::proptest! {
+            #[test]
+            fn base58check_round_trip(values: [u64; Digest::LEN], version: u8) {
+                let digest = Digest::new_from_u64s(values);
+                let encoded = digest.to_base58check(version);
+                proptest::prop_assert_eq!(Ok(digest), Digest::from_base58check(&encoded, version));
+            }
+        }
+    }
+
+    #[cfg(feature = "borsh")]
+    mod borsh_tests {
+        use super::*;
+
+        #[test]
+        fn borsh_encoding_of_known_digest() {
+            let digest = crate::digest!(1, 0, 0, 0, 0x0102);
+            let mut expected = vec![0; Digest::BYTES];
+            expected[0] = 1;
+            expected[32] = 2;
+            expected[33] = 1;
+            assert_eq!(expected, borsh::to_vec(&digest).unwrap());
+            assert_eq!(digest, borsh::from_slice(&expected).unwrap());
+        }
+
+        #[test]
+        fn borsh_rejects_corrupt_input() {
+            let mut bytes = borsh::to_vec(&varlen_digest()).unwrap();
+            assert!(borsh::from_slice::<Digest>(&bytes[..Digest::BYTES - 1]).is_err());
+
+            bytes.push(0);
+            assert!(borsh::from_slice::<Digest>(&bytes).is_err());
+            bytes.pop();
+
+            bytes[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+            assert!(borsh::from_slice::<Digest>(&bytes).is_err());
+        }
+
+        proptest::proptest! {
+            #[test]
+            fn borsh_round_trip(values: [u64; Digest::LEN]) {
+                let digest = Digest::new_from_u64s(values);
+                let bytes = borsh::to_vec(&digest).unwrap();
+                proptest::prop_assert_eq!(digest.to_bytes().to_vec(), bytes.clone());
+                proptest::prop_assert_eq!(digest, borsh::from_slice(&bytes).unwrap());
+            }
+        }
+    }
+
+    #[cfg(feature = "encodings")]
+    mod encodings_tests {
+        use super::*;
+
+        // computed with Python's `base64` module
+        const VARLEN_DIGEST_BASE64URL: &str =
+            "hQyjxebuBBib0rwf2TQkJbCXRj4MTZdzVDlB3E6ZiejKxmtsuA47MQ";
+        const VARLEN_DIGEST_BASE32: &str =
+            "QUGKHRPG5YCBRG6SXQP5SNBEEWYJORR6BRGZO42UHFA5YTUZRHUMVRTLNS4A4OZR";
+
+        #[test]
+        fn encodings_of_known_digest() {
+            let digest = varlen_digest();
+            assert_eq!(VARLEN_DIGEST_BASE64URL, digest.to_base64url());
+            assert_eq!(
+                format!("{VARLEN_DIGEST_BASE64URL}=="),
+                digest.to_base64url_padded()
+            );
+            assert_eq!(VARLEN_DIGEST_BASE32, digest.to_base32());
+
+            assert_eq!(Ok(digest), Digest::from_base64url(VARLEN_DIGEST_BASE64URL));
+            let padded = format!("{VARLEN_DIGEST_BASE64URL}==");
+            assert_eq!(Ok(digest), Digest::from_base64url_padded(&padded));
+            assert_eq!(Ok(digest), Digest::from_base32(VARLEN_DIGEST_BASE32));
+        }
+
+        #[test]
+        fn padding_variations_are_rejected() {
+            let err = Err(DecodeDigestError::InvalidPadding);
+            let unpadded = VARLEN_DIGEST_BASE64URL;
+            assert_eq!(err, Digest::from_base64url(&format!("{unpadded}==")));
+            assert_eq!(err, Digest::from_base64url(&format!("{unpadded}=")));
+            assert_eq!(err, Digest::from_base64url_padded(unpadded));
+            assert_eq!(err, Digest::from_base64url_padded(&format!("{unpadded}=")));
+            assert_eq!(
+                err,
+                Digest::from_base64url_padded(&format!("{unpadded}==="))
+            );
+            assert_eq!(
+                err,
+                Digest::from_base32(&format!("{VARLEN_DIGEST_BASE32}="))
+            );
+        }
+
+        #[test]
+        fn non_zero_unused_bits_are_rejected() {
+            let mut mutated = VARLEN_DIGEST_BASE64URL.to_string();
+            mutated.replace_range(53.., "R");
+            let err = Err(DecodeDigestError::InvalidPadding);
+            assert_eq!(err, Digest::from_base64url(&mutated));
+        }
+
+        #[test]
+        fn invalid_characters_and_lengths_are_rejected() {
+            let err = Err(DecodeDigestError::InvalidCharacters);
+            let standard_base64 = VARLEN_DIGEST_BASE64URL.replace('h', "+");
+            assert_eq!(err, Digest::from_base64url(&standard_base64));
+            let lowercase = VARLEN_DIGEST_BASE32.to_lowercase();
+            assert_eq!(err, Digest::from_base32(&lowercase));
+
+            let err = Err(DecodeDigestError::InvalidLength(53));
+            assert_eq!(err, Digest::from_base64url(&VARLEN_DIGEST_BASE64URL[1..]));
+            let err = Err(DecodeDigestError::InvalidLength(65));
+            let extended = format!("A{VARLEN_DIGEST_BASE32}");
+            assert_eq!(err, Digest::from_base32(&extended));
+        }
+
+        #[test]
+        fn non_canonical_limbs_are_rejected() {
+            let mut bytes = [0; Digest::BYTES];
+            bytes[Digest::BYTES - 8..].copy_from_slice(&u64::MAX.to_le_bytes());
+            let base64url = encode_bits(&bytes, BASE64URL_ALPHABET, 6);
+            let base32 = encode_bits(&bytes, BASE32_ALPHABET, 5);
+
+            let err = Err(DecodeDigestError::NotCanonical {
+                limb: 4,
+                value: u64::MAX,
+            });
+            assert_eq!(err, Digest::from_base64url(&base64url));
+            assert_eq!(err, Digest::from_base32(&base32));
+        }
+
+        proptest::proptest! {
+            #[test]
+            fn encodings_round_trip(values: [u64; Digest::LEN]) {
+                let digest = Digest::new_from_u64s(values);
+                proptest::prop_assert_eq!(Ok(digest), Digest::from_base64url(&digest.to_base64url()));
+                proptest::prop_assert_eq!(
+                    Ok(digest),
+                    Digest::from_base64url_padded(&digest.to_base64url_padded())
+                );
+                proptest::prop_assert_eq!(Ok(digest), Digest::from_base32(&digest.to_base32()));
+            }
+        }
+    }
+
+    #[cfg(feature = "num-bigint")]
+    mod num_bigint_tests {
+        use num_bigint::BigUint;
+
+        use super::*;
+
+        #[test]
+        fn biguint_of_known_digest() {
+            let digest = crate::digest!(1, 2, 3, 4, 5);
+            let expected = BigUint::from_slice(&[1, 0, 2, 0, 3, 0, 4, 0, 5, 0]);
+            assert_eq!(expected, BigUint::from(digest));
+        }
+
+        #[test]
+        fn maximal_digest_is_largest_convertible_integer() {
+            const MAX: u64 = BFieldElement::P - 1;
+            let max_digest = crate::digest!(MAX, MAX, MAX, MAX, MAX);
+            let max = BigUint::from(max_digest);
+            assert_eq!(Ok(max_digest), Digest::try_from(max.clone()));
+
+            let err = TryFromLimbsError::NotCanonical {
+                limb: 0,
+                value: BFieldElement::P,
+            };
+            assert_eq!(Err(err), Digest::try_from(max + 1_u32));
+        }
+
+        #[test]
+        fn integers_with_too_many_limbs_are_rejected() {
+            let too_large = BigUint::from(1_u32) << 320;
+            let err = TryFromLimbsError::InvalidLength(6);
+            assert_eq!(Err(err), Digest::try_from(too_large));
+        }
+
+        #[test]
+        fn small_integers_are_padded_with_zero_limbs() {
+            assert_eq!(Ok(Digest::ALL_ZEROS), Digest::try_from(BigUint::ZERO));
+            let digest = crate::digest!(42, 0, 0, 0, 0);
+            assert_eq!(Ok(digest), Digest::try_from(BigUint::from(42_u32)));
+        }
+
+        proptest::proptest! {
+            #[test]
+            fn biguint_round_trip(values: [u64; Digest::LEN]) {
+                let digest = Digest::new_from_u64s(values);
+                let value = BigUint::from(digest);
+                proptest::prop_assert_eq!(to_biguint(digest), value.clone());
+                proptest::prop_assert_eq!(Ok(digest), Digest::try_from(value));
+            }
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    mod proptest_tests {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn corner_cases_have_at_most_one_distinct_non_zero_value(
+                digest in digest_corner_cases(),
+            ) {
+                let non_zero = digest.iter().filter(|x| x.value() != 0).collect::<Vec<_>>();
+                let all_max = non_zero.len() == Digest::LEN
+                    && non_zero.iter().all(|x| x.value() == BFieldElement::P - 1);
+                prop_assert!(non_zero.len() <= 1 || all_max);
+            }
+        }
+
+        #[test]
+        fn arbitrary_digests_shrink_toward_all_zeros() {
+            use proptest::strategy::ValueTree;
+            use proptest::test_runner::TestRunner;
+
+            let mut runner = TestRunner::deterministic();
+            let mut tree = any::<Digest>().new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!(Digest::ALL_ZEROS, tree.current());
+        }
+    }
+
+    #[cfg(feature = "quickcheck")]
+    mod quickcheck_tests {
+        use quickcheck::Arbitrary;
+        use quickcheck::TestResult;
+        use quickcheck::quickcheck;
+
+        use super::*;
+        use crate::Tip5;
+
+        quickcheck! {
+            fn hash_pair_is_not_commutative(a: Digest, b: Digest) -> TestResult {
+                if a == b {
+                    return TestResult::discard();
+                }
+                TestResult::from_bool(Tip5::hash_pair(a, b) != Tip5::hash_pair(b, a))
+            }
+
+            fn shrinking_changes_exactly_one_element(digest: Digest) -> bool {
+                digest.shrink().all(|shrunk| {
+                    let num_changed = (0..Digest::LEN).filter(|&i| shrunk.0[i] != digest.0[i]).count();
+                    num_changed == 1
+                })
+            }
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    mod rand_tests {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        use super::*;
+
+        #[test]
+        fn sampling_is_deterministic_under_seeded_rng() {
+            let first = Digest::random(&mut StdRng::seed_from_u64(42));
+            let second = StdRng::seed_from_u64(42).r#gen::<Digest>();
+            assert_eq!(first, second);
+
+            let elements = StdRng::seed_from_u64(42).r#gen::<[BFieldElement; Digest::LEN]>();
+            assert_eq!(Digest::new(elements), first);
+
+            let third = Digest::random(&mut StdRng::seed_from_u64(43));
+            assert_ne!(first, third);
+        }
+
+        #[test]
+        fn sampled_limbs_are_canonical() {
+            let mut rng = StdRng::seed_from_u64(0);
+            for _ in 0..1_000 {
+                let digest = Digest::random(&mut rng);
+                let limbs = digest.values_as_u64s();
+                assert!(limbs.iter().all(|&limb| limb < BFieldElement::P));
+                assert_eq!(Ok(digest), Digest::try_from(limbs));
+            }
+        }
+    }
+
+    #[cfg(feature = "rkyv")]
+    mod rkyv_tests {
+        use rkyv::Deserialize;
+
+        use super::*;
+
+        fn digests() -> Vec<Digest> {
+            (0..10)
+                .map(|i| crate::Tip5::hash_varlen(&crate::bfe_array![i]))
+                .collect()
+        }
+
+        #[test]
+        fn archived_digests_can_be_accessed_without_deserialization() {
+            let digests = digests();
+            let bytes = rkyv::to_bytes::<_, 1024>(&digests).unwrap();
+            let archived = rkyv::check_archived_root::<Vec<Digest>>(&bytes).unwrap();
+
+            assert_eq!(digests.len(), archived.len());
+            for (digest, archived_digest) in digests.iter().zip(archived.iter()) {
+                assert_eq!(archived_digest, digest);
+                assert_eq!(*digest, Digest::from(archived_digest));
+                let limbs = archived_digest.values().map(|limb| limb.value());
+                assert_eq!(digest.values_as_u64s(), limbs);
+            }
+
+            let deserialized: Vec<Digest> = archived.deserialize(&mut rkyv::Infallible).unwrap();
+            assert_eq!(digests, deserialized);
+        }
+
+        #[test]
+        fn validation_rejects_corrupted_limb() {
+            let digests = digests();
+            let mut bytes = rkyv::to_bytes::<_, 1024>(&digests).unwrap();
+
+            let limb = digests[3].0[2].value().to_ne_bytes();
+            let position = bytes.windows(8).position(|window| window == limb).unwrap();
+            bytes[position..position + 8].copy_from_slice(&u64::MAX.to_ne_bytes());
+            assert!(rkyv::check_archived_root::<Vec<Digest>>(&bytes).is_err());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_tests {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        #[test]
+        fn json_encodes_hex_string() {
+            let digest = varlen_digest();
+            let json = serde_json::to_string(&digest).unwrap();
+            assert_eq!(format!(r#""{digest}""#), json);
+            assert_eq!(format!(r#""{VARLEN_DIGEST_HEX}""#), json);
+            assert_eq!(digest, serde_json::from_str(&json).unwrap());
+        }
+
+        #[test]
+        fn json_accepts_limb_arrays() {
+            let digest = crate::digest!(1, 2, 3, 4, 5);
+            assert_eq!(digest, serde_json::from_str("[1,2,3,4,5]").unwrap());
+            assert_eq!(
+                digest,
+                serde_json::from_str(r#"["1","2","3","4","5"]"#).unwrap()
+            );
+
+            let digest = varlen_digest();
+            let limbs = digest.values_as_u64s();
+            let json = format!("{limbs:?}").replace(' ', "");
+            assert_eq!(digest, serde_json::from_str(&json).unwrap());
+        }
+
+        #[test]
+        fn bincode_encodes_limbs_in_order() {
+            let digest = crate::digest!(1, 2, 3, 4, 5);
+            let bytes = bincode::serialize(&digest).unwrap();
+            let expected = [1_u64, 2, 3, 4, 5].map(u64::to_le_bytes).concat();
+            assert_eq!(expected, bytes);
+        }
+
+        #[test]
+        fn non_canonical_limbs_fail_to_deserialize() {
+            let p = BFieldElement::P;
+            let json = format!("[1,2,{p},4,5]");
+            let err = serde_json::from_str::<Digest>(&json).unwrap_err();
+            assert!(err.to_string().contains("not canonical"), "{err}");
+
+            let p_hex = p.to_le_bytes().map(|byte| format!("{byte:02x}")).concat();
+            let json = format!(r#""{p_hex}{}""#, &VARLEN_DIGEST_HEX[16..]);
+            let err = serde_json::from_str::<Digest>(&json).unwrap_err();
+            assert!(err.to_string().contains("limb 0"), "{err}");
+
+            let bytes = bincode::serialize(&[1, 2, p, 4, 5]).unwrap();
+            assert!(bincode::deserialize::<Digest>(&bytes).is_err());
+        }
+
+        #[test]
+        fn wrong_arities_fail_to_deserialize() {
+            for json in ["[]", "[1,2,3,4]", "[1,2,3,4,5,6]", "1", r#""""#, r#""00""#] {
+                assert!(serde_json::from_str::<Digest>(json).is_err(), "{json}");
+            }
+
+            let bytes = bincode::serialize(&[1_u64, 2, 3, 4]).unwrap();
+            assert!(bincode::deserialize::<Digest>(&bytes).is_err());
+        }
+
+        proptest! {
+            #[test]
+            fn serde_round_trip(values: [u64; Digest::LEN]) {
+                let digest = Digest::new_from_u64s(values);
+
+                let json = serde_json::to_string(&digest).unwrap();
+                prop_assert_eq!(digest, serde_json::from_str(&json).unwrap());
+
+                prop_assert_eq!(format!(r#""{digest}""#), json);
+
+                let bytes = bincode::serialize(&digest).unwrap();
+                let limbs = digest.values_as_u64s();
+                prop_assert_eq!(bincode::serialize(&limbs).unwrap(), bytes.clone());
+                prop_assert_eq!(digest, bincode::deserialize(&bytes).unwrap());
+            }
+        }
+    }
+
+    #[cfg(feature = "subtle")]
+    mod subtle_tests {
+        use proptest::prelude::*;
+        use subtle::ConstantTimeEq;
+
+        use super::*;
+
+        #[test]
+        fn ct_eq_on_digests_differing_in_a_single_limb() {
+            let digest = Digest::new_from_u64s([1, 2, 3, 4, 5]);
+            assert!(bool::from(digest.ct_eq(&digest)));
+            assert_eq!(Ok(()), digest.verify(&digest));
+
+            for i in 0..Digest::LEN {
+                let mut other = digest;
+                other.0[i] += BFieldElement::from(1_u8);
+                assert!(!bool::from(digest.ct_eq(&other)), "limb {i}");
+                assert_eq!(Err(VerificationError), digest.verify(&other));
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn ct_eq_agrees_with_eq(a: [u64; Digest::LEN], b: [u64; Digest::LEN]) {
+                let a = Digest::new_from_u64s(a);
+                let b = Digest::new_from_u64s(b);
+                prop_assert_eq!(a == b, bool::from(a.ct_eq(&b)));
+                prop_assert_eq!(a == b, bool::from(ConstantTimeEq::ct_eq(&a, &b)));
+                prop_assert_eq!(a == b, a.verify(&b).is_ok());
+                prop_assert!(a.verify(&a).is_ok());
+            }
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    mod zeroize_tests {
+        use zeroize::Zeroize;
+
+        use super::*;
+
+        #[test]
+        fn zeroize_clears_all_limbs() {
+            let mut digest = varlen_digest();
+            digest.zeroize();
+            assert_eq!(Digest::ALL_ZEROS, digest);
+            assert!(digest.iter().all(|x| x.raw_u64() == 0));
+        }
+
+        #[test]
+        fn secret_digest_is_zeroizable_and_redacted() {
+            let digest = varlen_digest();
+            let mut secret = SecretDigest::from(digest);
+            assert_eq!(digest, *secret.expose_secret());
+
+            let debug = format!("{secret:?}");
+            assert!(!debug.contains(&digest.to_string()));
+            for limb in digest {
+                assert!(!debug.contains(&limb.to_string()));
+                assert!(!debug.contains(&format!("{:x}", limb.value())));
+            }
+
+            secret.zeroize();
+            assert!(secret.expose_secret().iter().all(|x| x.raw_u64() == 0));
+        }
+
+        #[cfg(feature = "subtle")]
+        #[test]
+        fn secret_digests_compare_in_constant_time() {
+            use subtle::ConstantTimeEq;
+
+            let secret = SecretDigest::new(varlen_digest());
+            assert!(bool::from(secret.ct_eq(&secret.clone())));
+            assert!(!bool::from(secret.ct_eq(&SecretDigest::default())));
+        }
+    }
 }
@@ -0,0 +1,324 @@
+use num_traits::ConstZero;
+
+use crate::Domain;
+use crate::NotCanonicalError;
+use crate::RATE;
+use crate::STATE_SIZE;
+use crate::Sponge;
+use crate::Tip5;
+use crate::b_field_element::BFieldElement;
+use crate::digest::Digest;
+
+/// An incremental version of [`Tip5::hash_varlen`], for input that arrives in pieces.
+///
+/// Full blocks are absorbed as soon as they are complete; a partial block is buffered until
+/// more input arrives or the hasher is finalized. The digest equals [`Tip5::hash_varlen`] of
+/// the concatenation of all [updates](Self::update), regardless of how the input was split.
+///
+/// A hasher can be checkpointed at any point of the input, including in the middle of a block,
+/// with [`Self::export_state`] and [`Self::import_state`]. With feature `serde`, it is
+/// serialized as its sponge together with the buffered elements.
+pub struct Tip5Hasher {
+    sponge: Tip5,
+    buffer: [BFieldElement; RATE],
+    buffered: usize,
+}
+
+impl Default for Tip5Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resume hashing with a sponge that has absorbed some full blocks, e.g., one restored with
+/// [`Tip5::import_state`].
+impl From<Tip5> for Tip5Hasher {
+    fn from(sponge: Tip5) -> Self {
+        Self {
+            sponge,
+            ..Self::new()
+        }
+    }
+}
+
+impl Tip5Hasher {
+    pub const fn new() -> Self {
+        Self {
+            sponge: Tip5::new(Domain::VariableLength),
+            buffer: [BFieldElement::ZERO; RATE],
+            buffered: 0,
+        }
+    }
+
+    /// Feed more input into the hasher.
+    pub fn update(&mut self, mut input: &[BFieldElement]) {
+        if self.buffered > 0 {
+            let num_missing = (RATE - self.buffered).min(input.len());
+            let (completion, rest) = input.split_at(num_missing);
+            self.buffer[self.buffered..self.buffered + num_missing].copy_from_slice(completion);
+            self.buffered += num_missing;
+            input = rest;
+
+            if self.buffered < RATE {
+                return;
+            }
+            self.sponge.absorb(&self.buffer);
+            self.buffered = 0;
+        }
+
+        let (blocks, remainder) = input.as_chunks::<RATE>();
+        for block in blocks {
+            self.sponge.absorb(block);
+        }
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Export the canonical values of the sponge state and of the buffered elements, of which
+    /// there are fewer than [`RATE`]. Like for [`Tip5::export_state`], the
+    /// [absorb mode](Tip5::absorb_mode) is not part of the exported state.
+    ///
+    /// See also: [`Self::import_state`].
+    pub fn export_state(&self) -> ([u64; STATE_SIZE], Vec<u64>) {
+        let buffered = self.buffer[..self.buffered].iter();
+        let buffered = buffered.map(|element| element.value()).collect();
+        (self.sponge.export_state(), buffered)
+    }
+
+    /// Restore a hasher from a state previously produced by [`Self::export_state`]. The
+    /// restored hasher absorbs in [`AbsorbMode::Overwrite`](crate::AbsorbMode::Overwrite).
+    ///
+    /// Values that are not smaller than [`BFieldElement::P`] are rejected rather than reduced.
+    pub fn import_state(
+        state: [u64; STATE_SIZE],
+        buffered: &[u64],
+    ) -> Result<Self, ImportHasherStateError> {
+        if buffered.len() >= RATE {
+            return Err(ImportHasherStateError::TooManyBufferedElements(
+                buffered.len(),
+            ));
+        }
+
+        let mut hasher = Self::from(Tip5::import_state(state)?);
+        for (element, &value) in hasher.buffer.iter_mut().zip(buffered) {
+            *element = BFieldElement::try_from(value)?;
+        }
+        hasher.buffered = buffered.len();
+        Ok(hasher)
+    }
+
+    /// Apply the padding of [`Sponge::pad_and_absorb_all`] and produce the digest.
+    pub fn finalize(mut self) -> Digest {
+        self.sponge
+            .pad_and_absorb_all(&self.buffer[..self.buffered]);
+        self.sponge.finalize()
+    }
+
+    /// Like [`Self::finalize`], but resets the hasher for reuse instead of consuming it.
+    pub fn finalize_reset(&mut self) -> Digest {
+        std::mem::take(self).finalize()
+    }
+}
+
+/// The error returned when [importing](Tip5Hasher::import_state) the state of a [`Tip5Hasher`]
+/// fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportHasherStateError {
+    /// A value of the sponge state or of the buffered elements is not canonical.
+    NotCanonical(NotCanonicalError),
+
+    /// The given number of buffered elements is not smaller than [`RATE`]. A full block is
+    /// always absorbed right away.
+    TooManyBufferedElements(usize),
+}
+
+impl From<NotCanonicalError> for ImportHasherStateError {
+    fn from(error: NotCanonicalError) -> Self {
+        Self::NotCanonical(error)
+    }
+}
+
+impl std::fmt::Display for ImportHasherStateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotCanonical(error) => write!(f, "{error}"),
+            Self::TooManyBufferedElements(len) => {
+                write!(f, "{len} buffered elements, must be fewer than {RATE}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportHasherStateError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tip5Hasher {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.sponge, &self.buffer[..self.buffered]).serialize(serializer)
+    }
+}
+
+/// Fails if there are [`RATE`] or more buffered elements, since a full block is always
+/// absorbed right away.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Tip5Hasher {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (sponge, buffered): (Tip5, Vec<BFieldElement>) =
+            serde::Deserialize::deserialize(deserializer)?;
+        if buffered.len() >= RATE {
+            return Err(serde::de::Error::invalid_length(
+                buffered.len(),
+                &"fewer buffered elements than the rate",
+            ));
+        }
+
+        let mut hasher = Self::from(sponge);
+        hasher.buffer[..buffered.len()].copy_from_slice(&buffered);
+        hasher.buffered = buffered.len();
+        Ok(hasher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::bfe_array;
+
+    fn hash_in_pieces(input: &[BFieldElement], piece_lengths: &[usize]) -> Digest {
+        let mut hasher = Tip5Hasher::new();
+        let mut rest = input;
+        for &piece_length in piece_lengths {
+            let (piece, remainder) = rest.split_at(piece_length.min(rest.len()));
+            hasher.update(piece);
+            rest = remainder;
+        }
+        hasher.update(rest);
+        hasher.finalize()
+    }
+
+    #[test]
+    fn hasher_without_input_agrees_with_hash_varlen() {
+        assert_eq!(Tip5::hash_varlen(&[]), Tip5Hasher::new().finalize());
+
+        let mut hasher = Tip5Hasher::default();
+        hasher.update(&[]);
+        assert_eq!(Tip5::hash_varlen(&[]), hasher.finalize());
+
+        let num_permutations = crate::num_permutations_of(|| {
+            Tip5Hasher::new().finalize();
+        });
+        assert_eq!(1, num_permutations);
+    }
+
+    #[test]
+    fn hasher_agrees_with_hash_varlen_for_splits_around_block_boundaries() {
+        let input = (0..3 * RATE as u64).map(BFieldElement::new).collect_vec();
+        for len in 0..=input.len() {
+            let input = &input[..len];
+            let expected = Tip5::hash_varlen(input);
+            for split in 0..=len {
+                assert_eq!(expected, hash_in_pieces(input, &[split]));
+            }
+            assert_eq!(expected, hash_in_pieces(input, &[1; 3 * RATE]));
+            assert_eq!(expected, hash_in_pieces(input, &[RATE - 1, RATE, RATE + 1]));
+        }
+    }
+
+    #[test]
+    fn finalize_reset_allows_reuse() {
+        let mut hasher = Tip5Hasher::new();
+        hasher.update(&bfe_array![1, 2, 3]);
+        assert_eq!(
+            Tip5::hash_varlen(&bfe_array![1, 2, 3]),
+            hasher.finalize_reset()
+        );
+
+        hasher.update(&bfe_array![4, 5]);
+        assert_eq!(
+            Tip5::hash_varlen(&bfe_array![4, 5]),
+            hasher.finalize_reset()
+        );
+        assert_eq!(Tip5::hash_varlen(&[]), hasher.finalize());
+    }
+
+    #[test]
+    fn checkpointed_hash_agrees_with_uninterrupted_hash() {
+        let input = (0..1000).map(BFieldElement::new).collect_vec();
+        let (first_half, second_half) = input.split_at(input.len() / 2);
+
+        let mut uninterrupted = Tip5Hasher::new();
+        uninterrupted.update(&input);
+        let expected = uninterrupted.finalize();
+        assert_eq!(Tip5::hash_varlen(&input), expected);
+
+        let (state, buffered) = {
+            let mut hasher = Tip5Hasher::new();
+            hasher.update(first_half);
+            hasher.update(&second_half[..3]);
+            hasher.export_state()
+        };
+        assert_eq!(3, buffered.len());
+
+        let mut resumed = Tip5Hasher::import_state(state, &buffered).unwrap();
+        resumed.update(&second_half[3..]);
+        assert_eq!(expected, resumed.finalize());
+    }
+
+    #[test]
+    fn importing_invalid_hasher_state_fails() {
+        let (state, _) = Tip5Hasher::new().export_state();
+        assert_eq!(
+            Err(ImportHasherStateError::TooManyBufferedElements(RATE)),
+            Tip5Hasher::import_state(state, &[0; RATE]).map(|_| ())
+        );
+
+        let non_canonical = NotCanonicalError(BFieldElement::P);
+        assert_eq!(
+            Err(ImportHasherStateError::NotCanonical(non_canonical)),
+            Tip5Hasher::import_state(state, &[1, BFieldElement::P]).map(|_| ())
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_tests {
+        use super::*;
+
+        #[test]
+        fn hasher_resumes_from_checkpoint_with_partial_block() {
+            let input = (0..100).map(BFieldElement::new).collect_vec();
+            let expected = Tip5::hash_varlen(&input);
+
+            for split in [0, 1, RATE - 1, RATE, 33] {
+                let (first_part, second_part) = input.split_at(split);
+                let mut hasher = Tip5Hasher::new();
+                hasher.update(first_part);
+                let checkpoint = serde_json::to_string(&hasher).unwrap();
+
+                let mut resumed: Tip5Hasher = serde_json::from_str(&checkpoint).unwrap();
+                resumed.update(second_part);
+                assert_eq!(expected, resumed.finalize());
+            }
+        }
+
+        #[test]
+        fn deserializing_full_buffer_fails() {
+            let buffered = vec![BFieldElement::ZERO; RATE];
+            let json = serde_json::to_string(&(Tip5::init(), buffered)).unwrap();
+            assert!(serde_json::from_str::<Tip5Hasher>(&json).is_err());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn hasher_agrees_with_hash_varlen_for_random_splits(
+            values in prop::collection::vec(0..BFieldElement::P, 0..=100),
+            piece_lengths in prop::collection::vec(0..=2 * RATE, 0..=20),
+        ) {
+            let input = values.into_iter().map(BFieldElement::new).collect_vec();
+            prop_assert_eq!(Tip5::hash_varlen(&input), hash_in_pieces(&input, &piece_lengths));
+        }
+    }
+}
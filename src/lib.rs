@@ -1,14 +1,61 @@
+#[cfg(feature = "ark")]
+mod ark;
+#[cfg(feature = "ark")]
+pub use ark::{ArkGoldilocks, GoldilocksConfig, from_ark, to_ark};
+
+mod b_field_codec;
+pub use b_field_codec::{BFieldCodec, BFieldCodecError};
+
 mod b_field_element;
-use b_field_element::BFieldElement;
+#[cfg(feature = "rkyv")]
+pub use b_field_element::ArchivedBFieldElement;
+#[cfg(feature = "zeroize")]
+pub use b_field_element::SecretBFieldElement;
+#[cfg(feature = "proptest")]
+pub use b_field_element::bfield_element_corner_cases;
+pub use b_field_element::{
+    BFieldElement, BitDecompositionError, NotCanonicalError, ParseBFieldElementError,
+    TryFromBFieldElementError,
+};
 
 mod digest;
-use digest::Digest;
+#[cfg(feature = "rkyv")]
+pub use digest::ArchivedDigest;
+#[cfg(feature = "base58")]
+pub use digest::Base58CheckError;
+#[cfg(feature = "encodings")]
+pub use digest::DecodeDigestError;
+#[cfg(feature = "zeroize")]
+pub use digest::SecretDigest;
+#[cfg(feature = "subtle")]
+pub use digest::VerificationError;
+#[cfg(feature = "proptest")]
+pub use digest::digest_corner_cases;
+pub use digest::{
+    Digest, DigestBytesError, ParseDigestError, TryFromDigestError, TryFromLimbsError,
+};
+
+mod hasher;
+pub use hasher::{ImportHasherStateError, Tip5Hasher};
 
 mod mds;
 
+mod ntt;
+pub use ntt::{intt, ntt};
+
+#[cfg(feature = "ff")]
+mod prime_field;
+#[cfg(feature = "ff")]
+pub use prime_field::PrimeFieldElement;
+
 mod sponge;
-use itertools::Itertools;
-pub use sponge::{Domain, Sponge};
+pub use sponge::{
+    AbsorbMode, BYTES_PER_ELEMENT, DecodeIntegersError, Domain, DynSponge, Padding, Sponge,
+    SqueezeIter, decode_u64s, decode_u128s, encode_u64s, encode_u128s, hash_varlen, pack_bytes,
+};
+
+mod tip5_rate8;
+pub use tip5_rate8::Tip5Rate8;
 
 use num_traits::{ConstOne, ConstZero};
 
@@ -17,23 +64,44 @@ pub const NUM_SPLIT_AND_LOOKUP: usize = 4;
 pub const RATE: usize = 10;
 pub const NUM_ROUNDS: usize = 7;
 
-/// The lookup table with a high algebraic degree used in the TIP-5 permutation. To verify its
-/// correctness, see the test “lookup_table_is_correct.”
-const LOOKUP_TABLE: [u8; 256] = [
-    0, 7, 26, 63, 124, 215, 85, 254, 214, 228, 45, 185, 140, 173, 33, 240, 29, 177, 176, 32, 8,
-    110, 87, 202, 204, 99, 150, 106, 230, 14, 235, 128, 213, 239, 212, 138, 23, 130, 208, 6, 44,
-    71, 93, 116, 146, 189, 251, 81, 199, 97, 38, 28, 73, 179, 95, 84, 152, 48, 35, 119, 49, 88,
-    242, 3, 148, 169, 72, 120, 62, 161, 166, 83, 175, 191, 137, 19, 100, 129, 112, 55, 221, 102,
-    218, 61, 151, 237, 68, 164, 17, 147, 46, 234, 203, 216, 22, 141, 65, 57, 123, 12, 244, 54, 219,
-    231, 96, 77, 180, 154, 5, 253, 133, 165, 98, 195, 205, 134, 245, 30, 9, 188, 59, 142, 186, 197,
-    181, 144, 92, 31, 224, 163, 111, 74, 58, 69, 113, 196, 67, 246, 225, 10, 121, 50, 60, 157, 90,
-    122, 2, 250, 101, 75, 178, 159, 24, 36, 201, 11, 243, 132, 198, 190, 114, 233, 39, 52, 21, 209,
-    108, 238, 91, 187, 18, 104, 194, 37, 153, 34, 200, 143, 126, 155, 236, 118, 64, 80, 172, 89,
-    94, 193, 135, 183, 86, 107, 252, 13, 167, 206, 136, 220, 207, 103, 171, 160, 76, 182, 227, 217,
-    158, 56, 174, 4, 66, 109, 139, 162, 184, 211, 249, 47, 125, 232, 117, 43, 16, 42, 127, 20, 241,
-    25, 149, 105, 156, 51, 53, 168, 145, 247, 223, 79, 78, 226, 15, 222, 82, 115, 70, 210, 27, 41,
-    1, 170, 40, 131, 192, 229, 248, 255,
-];
+/// The lookup table with a high algebraic degree used in the TIP-5 permutation, _i.e._, the
+/// [offset Fermat cube map](Tip5::offset_fermat_cube_map) evaluated on every byte.
+const LOOKUP_TABLE: [u8; 256] = generate_lookup_table();
+
+/// The inverse of the lookup table used in the TIP-5 permutation, _i.e._, of the
+/// [offset Fermat cube map](Tip5::offset_fermat_cube_map) on bytes. Used by
+/// [`Tip5::inverse_split_and_lookup`].
+pub const INVERSE_LOOKUP_TABLE: [u8; 256] = generate_inverse_lookup_table();
+
+/// The inverse of 7 modulo P - 1, such that raising to this power inverts the power map of the
+/// S-box layer.
+const INVERSE_SBOX_EXPONENT: u64 = 10540996611094048183;
+
+const fn generate_lookup_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < table.len() {
+        let value = Tip5::offset_fermat_cube_map(i as u16);
+        assert!(value <= u8::MAX as u16);
+        table[i] = value as u8;
+        i += 1;
+    }
+    table
+}
+
+const fn generate_inverse_lookup_table() -> [u8; 256] {
+    let mut table = [0; 256];
+    let mut is_set = [false; 256];
+    let mut i = 0;
+    while i < table.len() {
+        let image = LOOKUP_TABLE[i] as usize;
+        assert!(!is_set[image], "lookup table must be a permutation");
+        table[image] = i as u8;
+        is_set[image] = true;
+        i += 1;
+    }
+    table
+}
 
 const ROUND_CONSTANTS: [BFieldElement; NUM_ROUNDS * STATE_SIZE] = [
     // 1st round constants
@@ -167,27 +235,136 @@ pub const MDS_MATRIX_FIRST_COLUMN: [i64; STATE_SIZE] = [
 
 pub struct Tip5 {
     pub state: [BFieldElement; STATE_SIZE],
+
+    /// How [absorb](Sponge::absorb) feeds input into the state. See [`AbsorbMode`].
+    pub absorb_mode: AbsorbMode,
+}
+
+/// The Tip5 permutation on a bare state, for callers that do not need a [`Tip5`] sponge.
+/// [`Tip5::permute`] delegates to this function.
+#[inline]
+pub fn permutation(state: &mut [BFieldElement; STATE_SIZE]) {
+    #[cfg(test)]
+    NUM_PERMUTATIONS.with(|num_permutations| num_permutations.set(num_permutations.get() + 1));
+
+    for i in 0..NUM_ROUNDS {
+        Tip5::round(state, i);
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// The number of calls to [`permutation`] on the current thread, for testing the cost of
+    /// hashing.
+    static NUM_PERMUTATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// The number of calls to [`permutation`] that `f` makes.
+#[cfg(test)]
+fn num_permutations_of(f: impl FnOnce()) -> usize {
+    let before = NUM_PERMUTATIONS.with(std::cell::Cell::get);
+    f();
+    NUM_PERMUTATIONS.with(std::cell::Cell::get) - before
+}
+
+/// The states of one [round](Tip5::apply_round) of the permutation: the state before the
+/// round and the state after each of its layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundTrace {
+    pub state_before: [BFieldElement; STATE_SIZE],
+    pub after_sbox: [BFieldElement; STATE_SIZE],
+    pub after_mds: [BFieldElement; STATE_SIZE],
+
+    /// The state after the round, which is the `state_before` of the next round.
+    pub after_constants: [BFieldElement; STATE_SIZE],
+}
+
+/// The trace of the permutation including the states within rounds, as returned by
+/// [`Tip5::detailed_trace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetailedTrace {
+    pub rounds: [RoundTrace; NUM_ROUNDS],
+}
+
+impl DetailedTrace {
+    /// Collapse to the states between rounds, as returned by [`Tip5::trace`].
+    pub fn to_trace(&self) -> [[BFieldElement; STATE_SIZE]; 1 + NUM_ROUNDS] {
+        let mut trace = [[BFieldElement::ZERO; STATE_SIZE]; 1 + NUM_ROUNDS];
+        trace[0] = self.rounds[0].state_before;
+        for (state, round) in trace[1..].iter_mut().zip(&self.rounds) {
+            *state = round.after_constants;
+        }
+        trace
+    }
+}
+
+/// The initial state of a sponge based on the Tip5 permutation with the given rate. Only the
+/// capacity, _i.e._, the elements from index `rate` on, depends on the [`Domain`].
+const fn initial_state(domain: Domain, rate: usize) -> [BFieldElement; STATE_SIZE] {
+    use Domain::*;
+
+    let mut state = [BFieldElement::ZERO; STATE_SIZE];
+
+    match domain {
+        VariableLength => (),
+        FixedLength => {
+            let mut i = rate;
+            while i < STATE_SIZE {
+                state[i] = BFieldElement::ONE;
+                i += 1;
+            }
+        }
+        Custom(tag) => {
+            state[rate] = BFieldElement::new(tag & 0xffff_ffff);
+            state[rate + 1] = BFieldElement::new(tag >> 32);
+            let mut i = rate + 2;
+            while i < STATE_SIZE {
+                state[i] = BFieldElement::new(2);
+                i += 1;
+            }
+        }
+    }
+
+    state
 }
 
 impl Tip5 {
     #[inline]
     pub const fn new(domain: Domain) -> Self {
-        use Domain::*;
+        Self::new_with_absorb_mode(domain, AbsorbMode::Overwrite)
+    }
 
-        let mut state = [BFieldElement::ZERO; STATE_SIZE];
+    /// Like [`Self::new`], but absorbing in the given [`AbsorbMode`]. Digests computed in
+    /// [`AbsorbMode::Add`] in general differ from those of [`Self::hash_varlen`].
+    #[inline]
+    pub const fn new_with_absorb_mode(domain: Domain, absorb_mode: AbsorbMode) -> Self {
+        let state = initial_state(domain, RATE);
+        Self { state, absorb_mode }
+    }
 
-        match domain {
-            VariableLength => (),
-            FixedLength => {
-                let mut i = RATE;
-                while i < STATE_SIZE {
-                    state[i] = BFieldElement::ONE;
-                    i += 1;
-                }
-            }
+    /// The canonical [values](BFieldElement::value) of the state, for checkpointing a sponge.
+    /// The [absorb mode](Self::absorb_mode) is not part of the exported state.
+    ///
+    /// See also: [`Self::import_state`].
+    pub fn export_state(&self) -> [u64; STATE_SIZE] {
+        self.state.map(|element| element.value())
+    }
+
+    /// Restore a sponge from a state previously produced by [`Self::export_state`]. The
+    /// restored sponge absorbs in [`AbsorbMode::Overwrite`]; change its
+    /// [absorb mode](Self::absorb_mode) if the exported sponge used a different one.
+    ///
+    /// Values that are not smaller than [`BFieldElement::P`] are rejected rather than reduced.
+    pub fn import_state(values: [u64; STATE_SIZE]) -> Result<Self, NotCanonicalError> {
+        let mut state = [BFieldElement::ZERO; STATE_SIZE];
+        for (element, value) in state.iter_mut().zip(values) {
+            *element = BFieldElement::try_from(value)?;
         }
 
-        Self { state }
+        Ok(Self {
+            state,
+            absorb_mode: AbsorbMode::Overwrite,
+        })
     }
 
     #[inline]
@@ -211,12 +388,39 @@ impl Tip5 {
         *element = BFieldElement::from_raw_bytes(&bytes);
     }
 
+    /// The inverse of the split-and-lookup map of the S-box layer: every byte of the internal
+    /// Montgomery representation is mapped through the [`INVERSE_LOOKUP_TABLE`].
+    #[inline]
+    pub fn inverse_split_and_lookup(element: &mut BFieldElement) {
+        let mut bytes = element.raw_bytes();
+        for byte in &mut bytes {
+            *byte = INVERSE_LOOKUP_TABLE[*byte as usize];
+        }
+
+        *element = BFieldElement::from_raw_bytes(&bytes);
+    }
+
+    /// The inverse of the S-box layer of a [round](Self::apply_round): the first
+    /// [`NUM_SPLIT_AND_LOOKUP`] elements are mapped by the
+    /// [inverse split-and-lookup map](Self::inverse_split_and_lookup), and every other
+    /// element is raised to the inverse of 7 modulo P - 1.
+    pub fn inverse_sbox_layer(state: &mut [BFieldElement; STATE_SIZE]) {
+        let (split_and_lookup_elements, power_map_elements) =
+            state.split_at_mut(NUM_SPLIT_AND_LOOKUP);
+        for element in split_and_lookup_elements {
+            Self::inverse_split_and_lookup(element);
+        }
+        for element in power_map_elements {
+            *element = element.mod_pow(INVERSE_SBOX_EXPONENT);
+        }
+    }
+
     #[inline(always)]
-    fn mds_generated(&mut self) {
+    fn mds_generated(state: &mut [BFieldElement; STATE_SIZE]) {
         let mut lo: [u64; STATE_SIZE] = [0; STATE_SIZE];
         let mut hi: [u64; STATE_SIZE] = [0; STATE_SIZE];
         for i in 0..STATE_SIZE {
-            let b = self.state[i].raw_u64();
+            let b = state[i].raw_u64();
             hi[i] = b >> 32;
             lo[i] = b & 0xffffffffu64;
         }
@@ -232,42 +436,69 @@ impl Tip5 {
 
             let (res, over) = s_lo.overflowing_add(s_hi * 0xffffffffu64);
 
-            self.state[r] =
-                BFieldElement::from_raw_u64(if over { res + 0xffffffffu64 } else { res });
+            state[r] = BFieldElement::from_raw_u64(if over { res + 0xffffffffu64 } else { res });
         }
     }
 
     #[inline(always)]
     #[allow(clippy::needless_range_loop)]
-    fn sbox_layer(&mut self) {
+    fn sbox_layer(state: &mut [BFieldElement; STATE_SIZE]) {
         for i in 0..NUM_SPLIT_AND_LOOKUP {
-            Self::split_and_lookup(&mut self.state[i]);
+            Self::split_and_lookup(&mut state[i]);
         }
 
         for i in NUM_SPLIT_AND_LOOKUP..STATE_SIZE {
-            let sq = self.state[i] * self.state[i];
+            let sq = state[i] * state[i];
             let qu = sq * sq;
-            self.state[i] *= sq * qu;
+            state[i] *= sq * qu;
         }
     }
 
     #[inline(always)]
-    fn round(&mut self, round_index: usize) {
-        self.sbox_layer();
-        self.mds_generated();
+    fn add_round_constants(state: &mut [BFieldElement; STATE_SIZE], round_index: usize) {
         for i in 0..STATE_SIZE {
-            self.state[i] += ROUND_CONSTANTS[round_index * STATE_SIZE + i];
+            state[i] += ROUND_CONSTANTS[round_index * STATE_SIZE + i];
         }
     }
 
     #[inline(always)]
-    fn permutation(&mut self) {
-        for i in 0..NUM_ROUNDS {
-            self.round(i);
-        }
+    fn round(state: &mut [BFieldElement; STATE_SIZE], round_index: usize) {
+        Self::sbox_layer(state);
+        Self::mds_generated(state);
+        Self::add_round_constants(state, round_index);
+    }
+
+    /// Apply the round with the given index to the [state](Self::state). A round consists of
+    /// 1. the S-box layer: each of the first [`NUM_SPLIT_AND_LOOKUP`] elements is split into
+    ///    the 8 bytes of its internal Montgomery representation, and every byte is mapped
+    ///    through the [lookup table](Self::offset_fermat_cube_map); each other element is
+    ///    raised to the 7th power,
+    /// 2. the MDS layer: multiplication with the circulant matrix defined by
+    ///    [`MDS_MATRIX_FIRST_COLUMN`], and
+    /// 3. the addition of the [`STATE_SIZE`] round constants of this round.
+    ///
+    /// Applying the rounds `0..NUM_ROUNDS` in order is the [permutation](Self::permute).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the round index is not smaller than [`NUM_ROUNDS`].
+    pub fn apply_round(&mut self, round_index: usize) {
+        assert!(
+            round_index < NUM_ROUNDS,
+            "round index must be smaller than {NUM_ROUNDS}, got {round_index}"
+        );
+        Self::round(&mut self.state, round_index);
     }
 
-    /// Functionally equivalent to [`permutation`](Self::permutation). Returns the trace of
+    /// Apply the Tip5 permutation, _i.e._, all [`NUM_ROUNDS`] rounds, to the entire
+    /// [state](Self::state). This is the raw primitive: there is no padding, and the
+    /// [`Domain`] and [`AbsorbMode`] play no role. Use it to build custom sponge modes.
+    #[inline(always)]
+    pub fn permute(&mut self) {
+        permutation(&mut self.state);
+    }
+
+    /// Functionally equivalent to [`permute`](Self::permute). Returns the trace of
     /// applying the permutation; that is, the initial state of the sponge as well as its state
     /// after each round.
     pub fn trace(&mut self) -> [[BFieldElement; STATE_SIZE]; 1 + NUM_ROUNDS] {
@@ -275,13 +506,37 @@ impl Tip5 {
 
         trace[0] = self.state;
         for i in 0..NUM_ROUNDS {
-            self.round(i);
+            Self::round(&mut self.state, i);
             trace[1 + i] = self.state;
         }
 
         trace
     }
 
+    /// Like [`Self::trace`], but additionally records the states between the layers of every
+    /// [round](Self::apply_round).
+    pub fn detailed_trace(&mut self) -> DetailedTrace {
+        let empty_round = RoundTrace {
+            state_before: [BFieldElement::ZERO; STATE_SIZE],
+            after_sbox: [BFieldElement::ZERO; STATE_SIZE],
+            after_mds: [BFieldElement::ZERO; STATE_SIZE],
+            after_constants: [BFieldElement::ZERO; STATE_SIZE],
+        };
+        let mut rounds = [empty_round; NUM_ROUNDS];
+
+        for (round_index, round) in rounds.iter_mut().enumerate() {
+            round.state_before = self.state;
+            Self::sbox_layer(&mut self.state);
+            round.after_sbox = self.state;
+            Self::mds_generated(&mut self.state);
+            round.after_mds = self.state;
+            Self::add_round_constants(&mut self.state, round_index);
+            round.after_constants = self.state;
+        }
+
+        DetailedTrace { rounds }
+    }
+
     /// Hash 10 [`BFieldElement`]s.
     ///
     /// There is no input-padding because the input length is fixed.
@@ -297,10 +552,10 @@ impl Tip5 {
         // absorb once
         sponge.state[..10].copy_from_slice(input);
 
-        sponge.permutation();
+        sponge.permute();
 
         // squeeze once
-        sponge.state[..Digest::LEN].try_into().unwrap()
+        *sponge.state.first_chunk().unwrap()
     }
 
     /// Hash two [`Digest`]s together.
@@ -314,10 +569,67 @@ impl Tip5 {
         sponge.state[..Digest::LEN].copy_from_slice(&left.values());
         sponge.state[Digest::LEN..2 * Digest::LEN].copy_from_slice(&right.values());
 
-        sponge.permutation();
+        sponge.permute();
 
-        let digest_values = sponge.state[..Digest::LEN].try_into().unwrap();
-        Digest::new(digest_values)
+        Digest::from_slice_unchecked(&sponge.state[..Digest::LEN])
+    }
+
+    /// Hash a [`Digest`] together with a `u64`, for example a leaf index or a counter.
+    ///
+    /// The value is split into its two 32-bit limbs, least significant first, like
+    /// [`encode_u64s`] does. Hence, no reduction modulo [`BFieldElement::P`] takes place, and
+    /// different values result in different inputs to the permutation. For values smaller
+    /// than 2^32, the result equals hashing the digest together with the value as a single
+    /// element.
+    ///
+    /// See [`Self::hash_digest_and_elements`] for details.
+    pub fn hash_digest_and_u64(digest: Digest, value: u64) -> Digest {
+        let limbs = [value as u32, (value >> 32) as u32].map(BFieldElement::from_u32);
+        Self::hash_digest_and_elements(digest, &limbs)
+    }
+
+    /// Hash a [`Digest`] together with up to [`Digest::LEN`] additional elements, using a
+    /// single permutation.
+    ///
+    /// The rate holds the digest's elements, followed by the given elements, followed by
+    /// zeros. Like for [`Self::hash_pair`], the capacity is initialized for
+    /// [fixed-length](Domain::FixedLength) input. The two functions are separated only by
+    /// this layout: `hash_digest_and_elements(a, &b.values())` equals `hash_pair(a, b)`, and
+    /// the zero-padding implies that appending zeros to `elements` does not change the
+    /// result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [`Digest::LEN`] elements are given.
+    pub fn hash_digest_and_elements(digest: Digest, elements: &[BFieldElement]) -> Digest {
+        assert!(
+            elements.len() <= Digest::LEN,
+            "at most {} elements can be hashed with a digest, got {}",
+            Digest::LEN,
+            elements.len()
+        );
+
+        let mut sponge = Self::new(Domain::FixedLength);
+        sponge.state[..Digest::LEN].copy_from_slice(&digest.values());
+        sponge.state[Digest::LEN..Digest::LEN + elements.len()].copy_from_slice(elements);
+
+        sponge.permute();
+
+        Digest::from_slice_unchecked(&sponge.state[..Digest::LEN])
+    }
+
+    /// Hash a value by its [encoding](BFieldCodec::encode).
+    ///
+    /// Values of types with [static length](BFieldCodec::static_length) 10 are hashed with
+    /// [`Self::hash_10`], in the [fixed-length](Domain::FixedLength) domain. For example, a
+    /// type encoding two [`Digest`]s hashes like [`Self::hash_pair`]. All other values are
+    /// hashed with [`Self::hash_varlen`].
+    pub fn hash<T: BFieldCodec>(value: &T) -> Digest {
+        let encoding = value.encode();
+        match <&[BFieldElement; RATE]>::try_from(encoding.as_slice()) {
+            Ok(input) if T::static_length() == Some(RATE) => Digest::new(Self::hash_10(input)),
+            _ => Self::hash_varlen(&encoding),
+        }
     }
 
     /// Hash a variable-length sequence of [`BFieldElement`].
@@ -337,50 +649,102 @@ impl Tip5 {
     ///
     /// See also: [`Self::hash_10`], [`Self::hash_pair`], [`Self::hash`].
     //
-    // - Apply the correct padding
-    // - [Sponge::pad_and_absorb_all()]
-    // - Read the digest from the resulting state.
+    // Other sponges can use the generic free function [`crate::hash_varlen`].
     pub fn hash_varlen(input: &[BFieldElement]) -> Digest {
-        let mut sponge = Self::init();
-        sponge.pad_and_absorb_all(input);
-        let produce: [BFieldElement; Digest::LEN] =
-            (&sponge.state[..Digest::LEN]).try_into().unwrap();
-
-        Digest::new(produce)
+        sponge::hash_varlen::<RATE, Self>(input)
     }
 }
 
-impl Sponge for Tip5 {
-    const RATE: usize = RATE;
-
+impl Sponge<RATE> for Tip5 {
     fn init() -> Self {
         Self::new(Domain::VariableLength)
     }
 
-    fn absorb(&mut self, input: [BFieldElement; RATE]) {
-        self.state[..RATE]
-            .iter_mut()
-            .zip_eq(&input)
-            .for_each(|(a, &b)| *a = b);
+    fn absorb(&mut self, input: &[BFieldElement; RATE]) {
+        match self.absorb_mode {
+            AbsorbMode::Overwrite => self.state[..RATE].copy_from_slice(input),
+            AbsorbMode::Add => {
+                for (state_element, &input_element) in self.state[..RATE].iter_mut().zip(input) {
+                    *state_element += input_element;
+                }
+            }
+        }
 
-        self.permutation();
+        self.permute();
     }
 
     fn squeeze(&mut self) -> [BFieldElement; RATE] {
-        let produce: [BFieldElement; RATE] = (&self.state[..RATE]).try_into().unwrap();
-        self.permutation();
+        let produce: [BFieldElement; RATE] = *self.state.first_chunk().unwrap();
+        self.permute();
 
         produce
     }
+
+    /// The digest is the first [`Digest::LEN`] elements of the state. Unlike the provided
+    /// method, this does not permute the state, which would be wasted work. Hence, squeezing
+    /// afterwards, which the trait rules out, would repeat the digest.
+    fn finalize(&mut self) -> Digest {
+        Digest::from_slice_unchecked(&self.state[..Digest::LEN])
+    }
+}
+
+/// Serializes the [state](Tip5::state), element by element like [`BFieldElement`], together
+/// with the [absorb mode](Tip5::absorb_mode). This allows checkpointing a long-running hash
+/// computation.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tip5 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.state, self.absorb_mode).serialize(serializer)
+    }
+}
+
+/// State elements that are not smaller than [`BFieldElement::P`] are rejected rather than
+/// reduced.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Tip5 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (state, absorb_mode) = serde::Deserialize::deserialize(deserializer)?;
+        Ok(Self { state, absorb_mode })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// The lookup table as it was hard-coded before being generated.
+    const LITERAL_LOOKUP_TABLE: [u8; 256] = [
+        0, 7, 26, 63, 124, 215, 85, 254, 214, 228, 45, 185, 140, 173, 33, 240, 29, 177, 176, 32, 8,
+        110, 87, 202, 204, 99, 150, 106, 230, 14, 235, 128, 213, 239, 212, 138, 23, 130, 208, 6,
+        44, 71, 93, 116, 146, 189, 251, 81, 199, 97, 38, 28, 73, 179, 95, 84, 152, 48, 35, 119, 49,
+        88, 242, 3, 148, 169, 72, 120, 62, 161, 166, 83, 175, 191, 137, 19, 100, 129, 112, 55, 221,
+        102, 218, 61, 151, 237, 68, 164, 17, 147, 46, 234, 203, 216, 22, 141, 65, 57, 123, 12, 244,
+        54, 219, 231, 96, 77, 180, 154, 5, 253, 133, 165, 98, 195, 205, 134, 245, 30, 9, 188, 59,
+        142, 186, 197, 181, 144, 92, 31, 224, 163, 111, 74, 58, 69, 113, 196, 67, 246, 225, 10,
+        121, 50, 60, 157, 90, 122, 2, 250, 101, 75, 178, 159, 24, 36, 201, 11, 243, 132, 198, 190,
+        114, 233, 39, 52, 21, 209, 108, 238, 91, 187, 18, 104, 194, 37, 153, 34, 200, 143, 126,
+        155, 236, 118, 64, 80, 172, 89, 94, 193, 135, 183, 86, 107, 252, 13, 167, 206, 136, 220,
+        207, 103, 171, 160, 76, 182, 227, 217, 158, 56, 174, 4, 66, 109, 139, 162, 184, 211, 249,
+        47, 125, 232, 117, 43, 16, 42, 127, 20, 241, 25, 149, 105, 156, 51, 53, 168, 145, 247, 223,
+        79, 78, 226, 15, 222, 82, 115, 70, 210, 27, 41, 1, 170, 40, 131, 192, 229, 248, 255,
+    ];
+
+    #[test]
+    fn lookup_table_agrees_with_literal_table() {
+        assert_eq!(LITERAL_LOOKUP_TABLE, LOOKUP_TABLE);
+    }
+
+    #[test]
+    fn lookup_table_is_a_permutation() {
+        let mut sorted = LOOKUP_TABLE;
+        sorted.sort_unstable();
+        let identity: [u8; 256] = std::array::from_fn(|i| i as u8);
+        assert_eq!(identity, sorted);
+    }
+
     #[test]
     fn test_hash_10() {
-        let input = [
+        let input = bfe_array![
             2_977_285_544_793_697_764,
             8_573_079_213_791_329_436,
             14_740_515_030_531_427_526,
@@ -391,8 +755,7 @@ mod tests {
             14_740_515_030_531_427_526,
             14_389_955_978_682_590_192,
             1_689_728_978_827_025_832,
-        ]
-        .map(BFieldElement::new);
+        ];
 
         let expected_output: [u64; 5] = [
             4_284_109_133_012_162_799,
@@ -411,17 +774,467 @@ mod tests {
 
     #[test]
     fn test_hash_varlen() {
-        let input = [1, 0].map(BFieldElement::new);
+        let input = bfe_array![1, 0];
 
-        let expected_output: [u64; 5] = [
+        let expected_output = digest!(
             1_730_770_831_742_798_981,
             2_676_322_185_709_933_211,
             8_329_210_750_824_781_744,
             16_756_092_452_590_401_876,
             3_547_445_316_740_171_466,
+        );
+
+        let output = Tip5::hash_varlen(&input);
+        assert_eq!(output, expected_output);
+    }
+
+    #[test]
+    fn hash_varlen_of_elements_converted_with_from() {
+        let input = [1_u32, 0].map(BFieldElement::from);
+        assert_eq!(
+            Tip5::hash_varlen(&bfe_array![1, 0]),
+            Tip5::hash_varlen(&input)
+        );
+    }
+
+    #[test]
+    fn finalize_does_not_permute() {
+        let mut sponge = Tip5::init();
+        sponge.pad_and_absorb_all(&bfe_array![1, 2, 3]);
+        let state = sponge.state;
+        let mut digest = Digest::ALL_ZEROS;
+        assert_eq!(0, crate::num_permutations_of(|| digest = sponge.finalize()));
+        assert_eq!(Digest::from_slice_unchecked(&state[..Digest::LEN]), digest);
+    }
+
+    #[test]
+    fn test_hash_digest_and_u64() {
+        let digest = Tip5::hash_varlen(&bfe_array![1, 0]);
+
+        let expected_output = digest!(
+            15_337_260_386_861_942_493,
+            10_646_986_653_571_061_354,
+            7_054_855_329_446_231_714,
+            13_271_209_316_229_497_369,
+            17_398_283_206_894_360_974,
+        );
+
+        let output = Tip5::hash_digest_and_u64(digest, 42);
+        assert_eq!(output, expected_output);
+    }
+
+    #[test]
+    fn test_hash_digest_and_elements() {
+        let digest = Tip5::hash_varlen(&bfe_array![1, 0]);
+
+        let expected_output = digest!(
+            5_354_768_747_923_289_068,
+            5_545_402_151_513_007_644,
+            12_743_521_711_516_012_765,
+            9_107_723_075_574_440_096,
+            13_879_541_075_024_725_263,
+        );
+
+        let output = Tip5::hash_digest_and_elements(digest, &bfe_array![1, 2, 3]);
+        assert_eq!(output, expected_output);
+    }
+
+    #[test]
+    fn hash_digest_and_elements_agrees_with_layout() {
+        let digest = Digest::new_from_u64s([1, 2, 3, 4, 5]);
+        let other = Digest::new_from_u64s([6, 7, 8, 9, 10]);
+        assert_eq!(
+            Tip5::hash_pair(digest, other),
+            Tip5::hash_digest_and_elements(digest, &other.values())
+        );
+
+        let expected = Tip5::hash_10(&bfe_array![1, 2, 3, 4, 5, 42, 0, 0, 0, 0]);
+        assert_eq!(expected, Tip5::hash_digest_and_u64(digest, 42).values());
+        assert_eq!(
+            Tip5::hash_digest_and_elements(digest, &[]),
+            Tip5::hash_digest_and_elements(digest, &bfe_array![0, 0])
+        );
+    }
+
+    #[test]
+    fn varying_the_u64_changes_the_digest() {
+        let digest = Tip5::hash_varlen(&bfe_array![1, 0]);
+        let p = BFieldElement::P;
+        let digests = (0..1_000)
+            .chain([p, p + 1, u64::MAX - p, u64::MAX])
+            .map(|value| Tip5::hash_digest_and_u64(digest, value))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(1_004, digests.len());
+        assert!(!digests.contains(&digest));
+
+        let limbs = bfe_array![42, 0];
+        assert_eq!(
+            Tip5::hash_digest_and_elements(digest, &limbs),
+            Tip5::hash_digest_and_u64(digest, 42)
+        );
+
+        let other_digest = Tip5::hash_varlen(&bfe_array![0, 1]);
+        assert_ne!(
+            Tip5::hash_digest_and_u64(digest, 0),
+            Tip5::hash_digest_and_u64(other_digest, 0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 5 elements")]
+    fn hash_digest_and_too_many_elements_panics() {
+        let digest = Digest::new_from_u64s([1, 2, 3, 4, 5]);
+        Tip5::hash_digest_and_elements(digest, &bfe_array![0; 6]);
+    }
+
+    fn hash_in_domain(domain: Domain, input: &[BFieldElement]) -> Digest {
+        let mut sponge = Tip5::new(domain);
+        sponge.pad_and_absorb_all(input);
+        Digest::from_slice_unchecked(&sponge.state[..Digest::LEN])
+    }
+
+    #[test]
+    fn custom_domains_separate_digests() {
+        let input = bfe_array![1, 2, 3];
+        let tags = [
+            0,
+            1,
+            2,
+            1 << 32,
+            u64::MAX,
+            BFieldElement::P,
+            BFieldElement::P + 1,
         ];
+        let mut digests = tags
+            .iter()
+            .map(|&tag| hash_in_domain(Domain::Custom(tag), &input))
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(tags.len(), digests.len());
+
+        digests.insert(hash_in_domain(Domain::VariableLength, &input));
+        digests.insert(hash_in_domain(Domain::FixedLength, &input));
+        assert_eq!(tags.len() + 2, digests.len());
+    }
+
+    #[test]
+    fn custom_domain_zero_differs_from_existing_domains() {
+        const CUSTOM: Tip5 = Tip5::new(Domain::Custom(0));
+        assert_ne!(Tip5::new(Domain::VariableLength).state, CUSTOM.state);
+        assert_ne!(Tip5::new(Domain::FixedLength).state, CUSTOM.state);
+
+        let capacity = &CUSTOM.state[RATE..];
+        assert_eq!(&bfe_array![0, 0, 2, 2, 2, 2], capacity);
+        assert!(CUSTOM.state[..RATE].iter().all(|x| x.value() == 0));
+
+        let tagged = Tip5::new(Domain::Custom(0x0123_4567_89ab_cdef));
+        let expected = bfe_array![0x89ab_cdef, 0x0123_4567, 2, 2, 2, 2];
+        assert_eq!(&expected, &tagged.state[RATE..]);
+    }
+
+    fn hash_varlen_additively(input: &[BFieldElement]) -> Digest {
+        let mut sponge = Tip5::new_with_absorb_mode(Domain::VariableLength, AbsorbMode::Add);
+        sponge.pad_and_absorb_all(input);
+        sponge.finalize()
+    }
+
+    #[test]
+    fn additive_absorption_vectors() {
+        let input = (0..15).map(BFieldElement::new).collect::<Vec<_>>();
+        let expected = Digest::new_from_u64s([
+            4212680841590388097,
+            8969581043562162082,
+            11424765122694392380,
+            15329835659510349133,
+            13043064192482649447,
+        ]);
+        assert_eq!(expected, hash_varlen_additively(&input));
+        assert_ne!(Tip5::hash_varlen(&input), hash_varlen_additively(&input));
+
+        // The rate of a fresh sponge is all zeros, so a single block is absorbed identically.
+        let expected = Digest::new_from_u64s([
+            11048995573592393898,
+            6655187932135147625,
+            8573492257662932655,
+            4379820112787053727,
+            3881663824627898703,
+        ]);
+        assert_eq!(expected, hash_varlen_additively(&[]));
+        assert_eq!(Tip5::hash_varlen(&[]), hash_varlen_additively(&[]));
+    }
+
+    #[test]
+    fn absorb_modes_differ_on_non_zero_state() {
+        let mut overwriting = Tip5::init();
+        overwriting.permute();
+        let mut adding = Tip5::new_with_absorb_mode(Domain::VariableLength, AbsorbMode::Add);
+        adding.state = overwriting.state;
+
+        let block = bfe_array![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let mut summed_state = adding.state;
+        for (state_element, &input_element) in summed_state.iter_mut().zip(&block) {
+            *state_element += input_element;
+        }
+
+        let mut expected = Tip5::new_with_absorb_mode(Domain::VariableLength, AbsorbMode::Add);
+        expected.state = summed_state;
+        expected.permute();
+
+        overwriting.absorb(&block);
+        adding.absorb(&block);
+        assert_ne!(overwriting.state, adding.state);
+        assert_eq!(expected.state, adding.state);
+    }
+
+    #[test]
+    fn default_absorb_mode_is_overwrite() {
+        assert_eq!(AbsorbMode::Overwrite, AbsorbMode::default());
+        assert_eq!(AbsorbMode::Overwrite, Tip5::init().absorb_mode);
+        assert_eq!(
+            AbsorbMode::Overwrite,
+            Tip5::new(Domain::FixedLength).absorb_mode
+        );
+    }
+
+    #[test]
+    fn permute_reproduces_fixed_length_hashing() {
+        let input = bfe_array![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let mut sponge = Tip5::new(Domain::FixedLength);
+        sponge.state[..RATE].copy_from_slice(&input);
+        sponge.permute();
+        assert_eq!(Tip5::hash_10(&input), sponge.state[..Digest::LEN]);
+
+        let left = Digest::new_from_u64s([1, 2, 3, 4, 5]);
+        let right = Digest::new_from_u64s([6, 7, 8, 9, 10]);
+        assert_eq!(
+            Tip5::hash_pair(left, right),
+            Digest::from_slice_unchecked(&sponge.state[..Digest::LEN])
+        );
+    }
+
+    #[test]
+    fn permute_reproduces_variable_length_hashing() {
+        let mut sponge = Tip5::new(Domain::VariableLength);
+        sponge.state[..4].copy_from_slice(&bfe_array![1, 2, 3, 1]);
+        sponge.permute();
+        let digest = Digest::from_slice_unchecked(&sponge.state[..Digest::LEN]);
+        assert_eq!(Tip5::hash_varlen(&bfe_array![1, 2, 3]), digest);
+    }
+
+    #[test]
+    fn inverse_lookup_table_inverts_lookup_table() {
+        for i in 0..=u8::MAX {
+            assert_eq!(
+                i,
+                INVERSE_LOOKUP_TABLE[LOOKUP_TABLE[usize::from(i)] as usize]
+            );
+            assert_eq!(
+                i,
+                LOOKUP_TABLE[INVERSE_LOOKUP_TABLE[usize::from(i)] as usize]
+            );
+        }
+    }
+
+    #[test]
+    fn inverse_sbox_exponent_inverts_seventh_power() {
+        let p_minus_one = u128::from(BFieldElement::P - 1);
+        assert_eq!(1, 7 * u128::from(INVERSE_SBOX_EXPONENT) % p_minus_one);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn inverse_split_and_lookup_inverts_split_and_lookup(value in 0..BFieldElement::P) {
+            let original = BFieldElement::new(value);
+            let mut element = original;
+            Tip5::split_and_lookup(&mut element);
+            Tip5::inverse_split_and_lookup(&mut element);
+            proptest::prop_assert_eq!(original, element);
+
+            Tip5::inverse_split_and_lookup(&mut element);
+            Tip5::split_and_lookup(&mut element);
+            proptest::prop_assert_eq!(original, element);
+        }
+
+        #[test]
+        fn inverse_power_map_inverts_power_map(value in 0..BFieldElement::P) {
+            let element = BFieldElement::new(value);
+            let seventh_power = element.mod_pow(7);
+            proptest::prop_assert_eq!(element, seventh_power.mod_pow(INVERSE_SBOX_EXPONENT));
+        }
+
+        #[test]
+        fn inverse_sbox_layer_inverts_sbox_layer(
+            values in proptest::array::uniform16(0..BFieldElement::P),
+        ) {
+            let original = values.map(BFieldElement::new);
+            let mut state = original;
+            Tip5::sbox_layer(&mut state);
+            Tip5::inverse_sbox_layer(&mut state);
+            proptest::prop_assert_eq!(original, state);
+        }
+    }
+
+    #[test]
+    fn applying_all_rounds_is_the_permutation() {
+        let mut sponge = Tip5::new(Domain::FixedLength);
+        sponge.state[..3].copy_from_slice(&bfe_array![1, 2, 3]);
+        let mut by_rounds = Tip5::import_state(sponge.export_state()).unwrap();
+
+        let trace = Tip5::import_state(sponge.export_state()).unwrap().trace();
+        for round_index in 0..NUM_ROUNDS {
+            assert_eq!(trace[round_index], by_rounds.state);
+            by_rounds.apply_round(round_index);
+            assert_eq!(trace[round_index + 1], by_rounds.state);
+        }
+
+        sponge.permute();
+        assert_eq!(sponge.state, by_rounds.state);
+    }
+
+    #[test]
+    fn detailed_trace_is_internally_consistent() {
+        let mut sponge = Tip5::new(Domain::VariableLength);
+        sponge.state[..4].copy_from_slice(&bfe_array![1, 2, 3, 1]);
+        let initial_state = sponge.state;
+        let detailed_trace = sponge.detailed_trace();
+
+        assert_eq!(initial_state, detailed_trace.rounds[0].state_before);
+        for (round_index, round) in detailed_trace.rounds.iter().enumerate() {
+            let mut state = round.state_before;
+            Tip5::sbox_layer(&mut state);
+            assert_eq!(round.after_sbox, state);
+            Tip5::mds_generated(&mut state);
+            assert_eq!(round.after_mds, state);
+            Tip5::add_round_constants(&mut state, round_index);
+            assert_eq!(round.after_constants, state);
+        }
+        for rounds in detailed_trace.rounds.windows(2) {
+            assert_eq!(rounds[0].after_constants, rounds[1].state_before);
+        }
+
+        let last_round = detailed_trace.rounds[NUM_ROUNDS - 1];
+        assert_eq!(last_round.after_constants, sponge.state);
+    }
+
+    #[test]
+    fn collapsed_detailed_trace_is_trace() {
+        let mut sponge = Tip5::new(Domain::FixedLength);
+        sponge.state[..3].copy_from_slice(&bfe_array![4, 5, 6]);
+        let mut other = Tip5::import_state(sponge.export_state()).unwrap();
 
-        let output = Tip5::hash_varlen(&input).values().map(|bfe| bfe.value());
-        assert_eq!(output, expected_output,);
+        assert_eq!(other.trace(), sponge.detailed_trace().to_trace());
+        assert_eq!(other.state, sponge.state);
+    }
+
+    #[test]
+    #[should_panic(expected = "round index must be smaller than 7, got 7")]
+    fn applying_out_of_range_round_panics() {
+        Tip5::init().apply_round(NUM_ROUNDS);
+    }
+
+    #[test]
+    fn permutation_of_bare_state_agrees_with_permute() {
+        let mut sponge = Tip5::new(Domain::FixedLength);
+        let mut state = sponge.state;
+        for _ in 0..10 {
+            sponge.permute();
+            permutation(&mut state);
+            assert_eq!(sponge.state, state);
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn permutation_of_random_state_agrees_with_permute(
+            values in proptest::array::uniform16(0..BFieldElement::P),
+        ) {
+            let mut state = values.map(BFieldElement::new);
+            let mut sponge = Tip5::import_state(values).unwrap();
+            sponge.permute();
+            permutation(&mut state);
+            proptest::prop_assert_eq!(sponge.state, state);
+        }
+    }
+
+    #[test]
+    fn exported_state_can_be_imported() {
+        let mut sponge = Tip5::new(Domain::FixedLength);
+        sponge.permute();
+        let values = sponge.export_state();
+        assert_eq!(sponge.state.map(|element| element.value()), values);
+
+        let imported = Tip5::import_state(values).unwrap();
+        assert_eq!(sponge.state, imported.state);
+        assert_eq!(AbsorbMode::Overwrite, imported.absorb_mode);
+    }
+
+    #[test]
+    fn importing_non_canonical_state_fails() {
+        let mut values = Tip5::init().export_state();
+        values[STATE_SIZE - 1] = BFieldElement::P;
+        let Err(err) = Tip5::import_state(values) else {
+            panic!("non-canonical state must be rejected");
+        };
+        assert_eq!(NotCanonicalError(BFieldElement::P), err);
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde_tests {
+        use super::*;
+
+        #[test]
+        fn serde_round_trip_preserves_state_and_absorb_mode() {
+            let mut sponge = Tip5::new_with_absorb_mode(Domain::Custom(42), AbsorbMode::Add);
+            sponge.pad_and_absorb_all(&bfe_array![1, 2, 3]);
+
+            let json = serde_json::to_string(&sponge).unwrap();
+            let restored: Tip5 = serde_json::from_str(&json).unwrap();
+            assert_eq!(sponge.state, restored.state);
+            assert_eq!(AbsorbMode::Add, restored.absorb_mode);
+        }
+
+        #[test]
+        fn deserializing_non_canonical_state_fails() {
+            let mut sponge = Tip5::init();
+            sponge.permute();
+            let json = serde_json::to_string(&sponge).unwrap();
+            let canonical = format!("\"{}\"", sponge.state[0].value());
+            let non_canonical = format!("\"{}\"", BFieldElement::P);
+            let json = json.replacen(&canonical, &non_canonical, 1);
+            assert!(serde_json::from_str::<Tip5>(&json).is_err());
+        }
+
+        #[test]
+        fn absorb_mode_is_serialized_by_name() {
+            assert_eq!(
+                r#""overwrite""#,
+                serde_json::to_string(&AbsorbMode::Overwrite).unwrap()
+            );
+            assert_eq!(r#""add""#, serde_json::to_string(&AbsorbMode::Add).unwrap());
+            assert!(serde_json::from_str::<AbsorbMode>(r#""xor""#).is_err());
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    mod proptest_tests {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn hash_pair_is_not_commutative(left: Digest, right: Digest) {
+                prop_assume!(left != right);
+                prop_assert_ne!(Tip5::hash_pair(left, right), Tip5::hash_pair(right, left));
+            }
+
+            #[test]
+            fn hash_pair_of_corner_cases_is_not_commutative(
+                left in digest_corner_cases(),
+                right in digest_corner_cases(),
+            ) {
+                prop_assume!(left != right);
+                prop_assert_ne!(Tip5::hash_pair(left, right), Tip5::hash_pair(right, left));
+            }
+        }
     }
 }
@@ -0,0 +1,173 @@
+//! The number-theoretic transform, _i.e._, the discrete Fourier transform over the field of
+//! [`BFieldElement`]s.
+
+use num_traits::ConstOne;
+
+use crate::b_field_element::BFieldElement;
+
+/// Evaluate the polynomial with the given coefficients on the multiplicative subgroup of
+/// order n = `x.len()`, in place. After the transform, `x[k]` holds the evaluation at ω^k,
+/// where ω is the [primitive n-th root of unity](BFieldElement::primitive_root_of_unity).
+///
+/// This is the iterative radix-2 Cooley–Tukey algorithm, taking O(n·log(n)) time.
+///
+/// # Panics
+///
+/// Panics if the length of `x` is not a power of two or is larger than 2^32.
+pub fn ntt(x: &mut [BFieldElement]) {
+    let omega = primitive_root_of_unity(x.len());
+    transform(x, omega);
+}
+
+/// The inverse of [`ntt`]: interpolate the given evaluations on the multiplicative subgroup of
+/// order n = `x.len()`, in place, resulting in the coefficients of the unique polynomial of
+/// degree less than n.
+///
+/// # Panics
+///
+/// Panics if the length of `x` is not a power of two or is larger than 2^32.
+pub fn intt(x: &mut [BFieldElement]) {
+    let omega = primitive_root_of_unity(x.len());
+    transform(x, omega.inverse());
+
+    let n_inverse = BFieldElement::new(x.len() as u64).inverse();
+    for element in x {
+        *element *= n_inverse;
+    }
+}
+
+fn primitive_root_of_unity(n: usize) -> BFieldElement {
+    let Some(omega) = BFieldElement::primitive_root_of_unity(n as u64) else {
+        panic!("NTT length must be a power of two not larger than 2^32, got {n}");
+    };
+    omega
+}
+
+fn transform(x: &mut [BFieldElement], omega: BFieldElement) {
+    let n = x.len();
+    let log_2_of_n = n.trailing_zeros();
+    bit_reverse_permutation(x, log_2_of_n);
+
+    let mut half_length = 1;
+    while half_length < n {
+        let length = 2 * half_length;
+        let twiddle_step = omega.mod_pow((n / length) as u64);
+        for chunk in x.chunks_exact_mut(length) {
+            let (lo, hi) = chunk.split_at_mut(half_length);
+            let mut twiddle = BFieldElement::ONE;
+            for (u, v) in lo.iter_mut().zip(hi) {
+                let t = *v * twiddle;
+                *v = *u - t;
+                *u += t;
+                twiddle *= twiddle_step;
+            }
+        }
+        half_length = length;
+    }
+}
+
+fn bit_reverse_permutation(x: &mut [BFieldElement], log_2_of_n: u32) {
+    if log_2_of_n == 0 {
+        return;
+    }
+
+    for i in 0..x.len() {
+        let j = i.reverse_bits() >> (usize::BITS - log_2_of_n);
+        if i < j {
+            x.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::ConstZero;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn naive_evaluation(coefficients: &[BFieldElement]) -> Vec<BFieldElement> {
+        let n = coefficients.len();
+        let omega = BFieldElement::primitive_root_of_unity(n as u64).unwrap();
+        (0..n as u64)
+            .map(|k| {
+                let point = omega.mod_pow(k);
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(BFieldElement::ZERO, |acc, &c| acc * point + c)
+            })
+            .collect()
+    }
+
+    fn elements(log_2_of_n: u32) -> impl Strategy<Value = Vec<BFieldElement>> {
+        let n = 1 << log_2_of_n;
+        proptest::collection::vec(any::<u64>(), n)
+            .prop_map(|values| values.into_iter().map(BFieldElement::new).collect())
+    }
+
+    #[test]
+    fn ntt_of_constant_polynomial_is_constant() {
+        let mut x = crate::bfe_vec![42, 0, 0, 0, 0, 0, 0, 0];
+        ntt(&mut x);
+        assert_eq!(crate::bfe_vec![42; 8], x);
+    }
+
+    #[test]
+    fn ntt_of_single_element_is_identity() {
+        let mut x = crate::bfe_vec![7];
+        ntt(&mut x);
+        assert_eq!(crate::bfe_vec![7], x);
+        intt(&mut x);
+        assert_eq!(crate::bfe_vec![7], x);
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn ntt_panics_on_length_that_is_not_a_power_of_two() {
+        ntt(&mut crate::bfe_array![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn intt_panics_on_empty_input() {
+        intt(&mut []);
+    }
+
+    proptest! {
+        #[test]
+        fn ntt_agrees_with_naive_evaluation(x in (0_u32..=6).prop_flat_map(elements)) {
+            let mut transformed = x.clone();
+            ntt(&mut transformed);
+            prop_assert_eq!(naive_evaluation(&x), transformed);
+        }
+
+        #[test]
+        fn intt_inverts_ntt(x in (0_u32..=10).prop_flat_map(elements)) {
+            let mut round_tripped = x.clone();
+            ntt(&mut round_tripped);
+            intt(&mut round_tripped);
+            prop_assert_eq!(&x, &round_tripped);
+
+            intt(&mut round_tripped);
+            ntt(&mut round_tripped);
+            prop_assert_eq!(x, round_tripped);
+        }
+
+        #[test]
+        fn ntt_is_linear(
+            (x, y) in (0_u32..=8).prop_flat_map(|log_n| (elements(log_n), elements(log_n))),
+            scalar: u64,
+        ) {
+            let scalar = BFieldElement::new(scalar);
+            let mut combination = x.iter().zip(&y).map(|(&x, &y)| scalar * x + y).collect::<Vec<_>>();
+            ntt(&mut combination);
+
+            let (mut x, mut y) = (x, y);
+            ntt(&mut x);
+            ntt(&mut y);
+            let expected = x.iter().zip(&y).map(|(&x, &y)| scalar * x + y).collect::<Vec<_>>();
+            prop_assert_eq!(expected, combination);
+        }
+    }
+}
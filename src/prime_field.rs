@@ -0,0 +1,395 @@
+//! Implementations of the [`ff`] traits [`Field`] and [`PrimeField`].
+
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use ff::{Field, PrimeField};
+use rand_core::RngCore;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeLess, CtOption};
+
+use crate::b_field_element::BFieldElement;
+
+/// Apart from [`invert`](Field::invert), the implementations are not constant-time; see
+/// [`BFieldElement::mod_pow_ct`] for details.
+impl Field for BFieldElement {
+    const ZERO: Self = <Self as num_traits::ConstZero>::ZERO;
+    const ONE: Self = <Self as num_traits::ConstOne>::ONE;
+
+    /// Samples through the [`Standard`](rand::distributions::Standard) distribution.
+    fn random(mut rng: impl RngCore) -> Self {
+        rand::Rng::r#gen(&mut rng)
+    }
+
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        let is_non_zero = !self.ct_eq(&Self::ZERO);
+        CtOption::new(self.inverse_or_zero(), is_non_zero)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        match BFieldElement::sqrt(self) {
+            Some(root) => CtOption::new(root, Choice::from(1)),
+            None => CtOption::new(Self::ZERO, Choice::from(0)),
+        }
+    }
+
+    /// Follows [`ff::helpers::sqrt_ratio_generic`], which requires [`PrimeField`].
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        // the 2^32-th root of unity is a non-square, so exactly one of a and b is a square,
+        // unless both are zero
+        let a = div.inverse_or_zero() * *num;
+        let b = a * BFieldElement::TWO_ADIC_ROOT_OF_UNITY;
+        let sqrt_a = Field::sqrt(&a);
+        let sqrt_b = Field::sqrt(&b);
+
+        let num_is_zero = num.ct_eq(&Self::ZERO);
+        let div_is_zero = div.ct_eq(&Self::ZERO);
+        let is_square = sqrt_a.is_some();
+        let root = Self::conditional_select(
+            &sqrt_b.unwrap_or(Self::ZERO),
+            &sqrt_a.unwrap_or(Self::ZERO),
+            is_square,
+        );
+
+        (is_square & (num_is_zero | !div_is_zero), root)
+    }
+}
+
+/// A [`BFieldElement`] implementing [`PrimeField`].
+///
+/// [`PrimeField`] requires an infallible `From<u64>`, which is incompatible with the strict
+/// `TryFrom<u64>` conversion of [`BFieldElement`]. This wrapper's `From<u64>` reduces modulo
+/// [`BFieldElement::P`] instead. The [representation](PrimeField::Repr) is the canonical value
+/// in little-endian byte order, as produced by [`BFieldElement::to_le_bytes`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct PrimeFieldElement(pub BFieldElement);
+
+impl From<BFieldElement> for PrimeFieldElement {
+    #[inline]
+    fn from(element: BFieldElement) -> Self {
+        Self(element)
+    }
+}
+
+impl From<PrimeFieldElement> for BFieldElement {
+    #[inline]
+    fn from(element: PrimeFieldElement) -> Self {
+        element.0
+    }
+}
+
+impl From<u64> for PrimeFieldElement {
+    /// Reduces the value modulo [`BFieldElement::P`].
+    #[inline]
+    fn from(value: u64) -> Self {
+        Self(BFieldElement::new(value))
+    }
+}
+
+/// Implement a binary operator and its compound assignment counterpart on
+/// [`PrimeFieldElement`], for owned and borrowed right-hand sides, by delegating to
+/// [`BFieldElement`].
+macro_rules! impl_op_for_prime_field_element {
+    ($($op:ident::$method:ident, $assign_op:ident::$assign_method:ident);+ $(;)?) => {$(
+        impl $op for PrimeFieldElement {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, rhs: Self) -> Self {
+                Self($op::$method(self.0, rhs.0))
+            }
+        }
+
+        impl $op<&PrimeFieldElement> for PrimeFieldElement {
+            type Output = Self;
+
+            #[inline]
+            fn $method(self, rhs: &Self) -> Self {
+                Self($op::$method(self.0, rhs.0))
+            }
+        }
+
+        impl $assign_op for PrimeFieldElement {
+            #[inline]
+            fn $assign_method(&mut self, rhs: Self) {
+                $assign_op::$assign_method(&mut self.0, rhs.0)
+            }
+        }
+
+        impl $assign_op<&PrimeFieldElement> for PrimeFieldElement {
+            #[inline]
+            fn $assign_method(&mut self, rhs: &Self) {
+                $assign_op::$assign_method(&mut self.0, rhs.0)
+            }
+        }
+    )+};
+}
+
+impl_op_for_prime_field_element!(
+    Add::add, AddAssign::add_assign;
+    Sub::sub, SubAssign::sub_assign;
+    Mul::mul, MulAssign::mul_assign;
+);
+
+impl Neg for PrimeFieldElement {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Sum for PrimeFieldElement {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|x| x.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a PrimeFieldElement> for PrimeFieldElement {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        Self(iter.map(|x| x.0).sum())
+    }
+}
+
+impl Product for PrimeFieldElement {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|x| x.0).product())
+    }
+}
+
+impl<'a> Product<&'a PrimeFieldElement> for PrimeFieldElement {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        Self(iter.map(|x| x.0).product())
+    }
+}
+
+impl ConstantTimeEq for PrimeFieldElement {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl ConditionallySelectable for PrimeFieldElement {
+    #[inline]
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self(BFieldElement::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl Field for PrimeFieldElement {
+    const ZERO: Self = Self(BFieldElement::ZERO);
+    const ONE: Self = Self(BFieldElement::ONE);
+
+    fn random(rng: impl RngCore) -> Self {
+        Self(BFieldElement::random(rng))
+    }
+
+    fn square(&self) -> Self {
+        Self(self.0.square())
+    }
+
+    fn double(&self) -> Self {
+        Self(Field::double(&self.0))
+    }
+
+    fn invert(&self) -> CtOption<Self> {
+        Field::invert(&self.0).map(Self)
+    }
+
+    fn sqrt(&self) -> CtOption<Self> {
+        Field::sqrt(&self.0).map(Self)
+    }
+
+    fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+        let (is_square, root) = BFieldElement::sqrt_ratio(&num.0, &div.0);
+        (is_square, Self(root))
+    }
+}
+
+impl PrimeField for PrimeFieldElement {
+    type Repr = [u8; 8];
+
+    const MODULUS: &'static str = "0xffffffff00000001";
+    const NUM_BITS: u32 = 64;
+    const CAPACITY: u32 = 63;
+    const TWO_INV: Self = Self(BFieldElement::new(BFieldElement::P.div_ceil(2)));
+    const MULTIPLICATIVE_GENERATOR: Self = Self(BFieldElement::GENERATOR);
+    const S: u32 = BFieldElement::TWO_ADICITY;
+    const ROOT_OF_UNITY: Self = Self(BFieldElement::TWO_ADIC_ROOT_OF_UNITY);
+    const ROOT_OF_UNITY_INV: Self = Self(BFieldElement::new(8_554_224_884_056_360_729));
+
+    // GENERATOR^(2^S)
+    const DELTA: Self = Self(BFieldElement::new(12_275_445_934_081_160_404));
+
+    fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+        let value = u64::from_le_bytes(repr);
+        let is_canonical = value.ct_lt(&BFieldElement::P);
+        CtOption::new(Self(BFieldElement::new(value)), is_canonical)
+    }
+
+    fn to_repr(&self) -> Self::Repr {
+        self.0.to_le_bytes()
+    }
+
+    fn is_odd(&self) -> Choice {
+        Choice::from((self.0.value() & 1) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Debug-friendly wrapper, as [`CtOption`] implements neither [`PartialEq`] nor
+    /// [`Debug`](std::fmt::Debug) in a useful way.
+    fn option<T>(ct_option: CtOption<T>) -> Option<T> {
+        ct_option.into()
+    }
+
+    #[test]
+    fn constants_agree_with_field_parameters() {
+        let p = BFieldElement::P;
+        let modulus = u64::from_str_radix(&PrimeFieldElement::MODULUS[2..], 16).unwrap();
+        assert_eq!(p, modulus);
+        assert_eq!(u64::BITS - p.leading_zeros(), PrimeFieldElement::NUM_BITS);
+        assert_eq!(PrimeFieldElement::NUM_BITS - 1, PrimeFieldElement::CAPACITY);
+
+        let two = PrimeFieldElement::from(2);
+        assert_eq!(PrimeFieldElement::ONE, two * PrimeFieldElement::TWO_INV);
+
+        let s = PrimeFieldElement::S;
+        let t = (p - 1) >> s;
+        assert_eq!(1, t % 2);
+        assert_eq!(p - 1, t << s);
+
+        let generator = PrimeFieldElement::MULTIPLICATIVE_GENERATOR;
+        for prime_factor in [2, 3, 5, 17, 257, 65_537] {
+            let exponent = (p - 1) / prime_factor;
+            assert_ne!(BFieldElement::ONE, generator.0.mod_pow(exponent));
+        }
+
+        let root = PrimeFieldElement::ROOT_OF_UNITY;
+        assert_eq!(generator.0.mod_pow(t), root.0);
+        assert_eq!(BFieldElement::ONE, root.0.mod_pow(1 << s));
+        assert_ne!(BFieldElement::ONE, root.0.mod_pow(1 << (s - 1)));
+        let root_inv = PrimeFieldElement::ROOT_OF_UNITY_INV;
+        assert_eq!(PrimeFieldElement::ONE, root * root_inv);
+
+        let delta = PrimeFieldElement::DELTA;
+        assert_eq!(generator.0.mod_pow(1 << s), delta.0);
+        assert_eq!(BFieldElement::ONE, delta.0.mod_pow(t));
+    }
+
+    /// An RNG replaying the given words.
+    struct Replay(std::vec::IntoIter<u64>);
+
+    impl rand_core::RngCore for Replay {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0.next().unwrap()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(8) {
+                let bytes = self.next_u64().to_le_bytes();
+                chunk.copy_from_slice(&bytes[..chunk.len()]);
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn random_rejects_non_canonical_words() {
+        let words = vec![u64::MAX, BFieldElement::P, 42];
+        let x = BFieldElement::random(Replay(words.into_iter()));
+        assert_eq!(BFieldElement::new(42), x);
+    }
+
+    #[test]
+    fn zero_has_no_inverse() {
+        assert_eq!(None, option(Field::invert(&BFieldElement::ZERO)));
+        assert_eq!(None, option(PrimeFieldElement::ZERO.invert()));
+    }
+
+    #[test]
+    fn non_canonical_repr_is_rejected() {
+        for value in [BFieldElement::P, u64::MAX] {
+            let repr = value.to_le_bytes();
+            assert_eq!(None, option(PrimeFieldElement::from_repr(repr)));
+        }
+
+        let largest = BFieldElement::P - 1;
+        let repr = largest.to_le_bytes();
+        let expected = PrimeFieldElement::from(largest);
+        assert_eq!(Some(expected), option(PrimeFieldElement::from_repr(repr)));
+    }
+
+    #[test]
+    fn sqrt_ratio_edge_cases() {
+        let zero = BFieldElement::ZERO;
+        let one = BFieldElement::ONE;
+        assert!(bool::from(BFieldElement::sqrt_ratio(&zero, &zero).0));
+        assert!(bool::from(BFieldElement::sqrt_ratio(&zero, &one).0));
+        assert!(!bool::from(BFieldElement::sqrt_ratio(&one, &zero).0));
+    }
+
+    proptest! {
+        #[test]
+        fn field_arithmetic_agrees_with_native_arithmetic(a: u64, b: u64) {
+            let (a, b) = (BFieldElement::new(a), BFieldElement::new(b));
+            let (ff_a, ff_b) = (PrimeFieldElement(a), PrimeFieldElement(b));
+
+            prop_assert_eq!(a + b, (ff_a + ff_b).0);
+            prop_assert_eq!(a - b, (ff_a - ff_b).0);
+            prop_assert_eq!(a * b, (ff_a * ff_b).0);
+            prop_assert_eq!(-a, (-ff_a).0);
+            prop_assert_eq!(a.square(), ff_a.square().0);
+            prop_assert_eq!(a + a, ff_a.double().0);
+            prop_assert_eq!(a.mod_pow(b.value()), ff_a.pow_vartime([b.value()]).0);
+            prop_assert_eq!(a.try_inverse(), option(ff_a.invert()).map(|x| x.0));
+            prop_assert_eq!(a.sqrt(), option(ff_a.sqrt()).map(|x| x.0));
+        }
+
+        #[test]
+        fn sqrt_ratio_agrees_with_native_arithmetic(num: u64, div in 1..BFieldElement::P) {
+            let (num, div) = (BFieldElement::new(num), BFieldElement::new(div));
+            let (is_square, root) = BFieldElement::sqrt_ratio(&num, &div);
+
+            let ratio = num / div;
+            prop_assert_eq!(ratio.is_square(), bool::from(is_square));
+            if ratio.is_square() {
+                prop_assert_eq!(ratio, root.square());
+            } else {
+                prop_assert_eq!(ratio * BFieldElement::TWO_ADIC_ROOT_OF_UNITY, root.square());
+            }
+        }
+
+        #[test]
+        fn repr_round_trip(value in 0..BFieldElement::P) {
+            let x = PrimeFieldElement::from(value);
+            prop_assert_eq!(value.to_le_bytes(), x.to_repr());
+            prop_assert_eq!(Some(x), option(PrimeFieldElement::from_repr(x.to_repr())));
+            prop_assert_eq!(value % 2 == 1, bool::from(x.is_odd()));
+        }
+
+    }
+}
@@ -1,7 +1,5 @@
-use std::iter;
-
 use crate::b_field_element::BFieldElement;
-use itertools::Itertools;
+use crate::digest::Digest;
 use num_traits::ConstOne;
 use num_traits::ConstZero;
 
@@ -21,33 +19,1108 @@ pub enum Domain {
     /// The `FixedLength` domain is used for hashing objects that always fit within [RATE] number
     /// of fields elements, e.g. a pair of [Digest](crate::math::digest::Digest)s.
     FixedLength,
+
+    /// A `Custom` domain separates application-level object types, e.g., blocks and
+    /// transactions, by a tag. The capacity holds the low and the high 32 bits of the tag,
+    /// followed by all twos. Since no other domain initializes the capacity with twos,
+    /// different tags and the other domains never share an initial state.
+    Custom(u64),
+}
+
+/// The [AbsorbMode] determines how a block of input enters the rate part of the sponge state.
+///
+/// The two modes produce incompatible digests: hashing the same input in different modes gives
+/// different results as soon as more than one block is absorbed, or, in general, whenever the
+/// rate part of the state is non-zero when absorbing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AbsorbMode {
+    /// The input overwrites the rate part of the state. This is what Tip5 specifies.
+    #[default]
+    Overwrite,
+
+    /// The input is added to the rate part of the state, element by element, like in the classic
+    /// duplex construction. Use this mode only for interoperability with protocols that specify
+    /// additive absorption.
+    Add,
+}
+
+/// Human-readable formats like JSON get the lower-case name of the mode, binary formats get its
+/// discriminant as a u8.
+#[cfg(feature = "serde")]
+impl serde::Serialize for AbsorbMode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_u8(*self as u8);
+        }
+
+        match self {
+            Self::Overwrite => serializer.serialize_str("overwrite"),
+            Self::Add => serializer.serialize_str("add"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AbsorbMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        use serde::de::Unexpected;
+
+        if !deserializer.is_human_readable() {
+            return match u8::deserialize(deserializer)? {
+                0 => Ok(Self::Overwrite),
+                1 => Ok(Self::Add),
+                other => Err(D::Error::invalid_value(
+                    Unexpected::Unsigned(other.into()),
+                    &"0 or 1",
+                )),
+            };
+        }
+
+        match String::deserialize(deserializer)?.as_str() {
+            "overwrite" => Ok(Self::Overwrite),
+            "add" => Ok(Self::Add),
+            other => Err(D::Error::unknown_variant(other, &["overwrite", "add"])),
+        }
+    }
+}
+
+/// The [Padding] scheme determines how [`Sponge::pad_and_absorb_all_with`] extends the input to a
+/// multiple of the rate.
+///
+/// The schemes produce different digests for the same input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// Append a one, then zeros up to the end of the block. This is the padding of
+    /// [`Sponge::pad_and_absorb_all`] and thus of [`Tip5::hash_varlen`](crate::Tip5::hash_varlen).
+    #[default]
+    OneZeroStar,
+
+    /// Append a one, then zeros, and set the last element of the final block to one. The padding
+    /// is at least two elements; if only one element of the last block is free, an additional
+    /// block is absorbed. Also known as the “10*1” padding rule.
+    TenStarOne,
 }
 
 /// A [cryptographic sponge][sponge]. Should only be based on a cryptographic permutation, e.g.,
 /// [`Tip5`][tip5].
 ///
+/// The rate is the const generic parameter `RATE`, which defaults to the rate of
+/// [`Tip5`][tip5], and determines the length of the arrays that are absorbed and squeezed.
+///
+/// Since version 0.2.0, there is no associated constant `RATE` anymore. Callers migrating from
+/// earlier versions use the const generic parameter, or [`RATE`](crate::RATE) for Tip5.
+///
 /// [sponge]: https://keccak.team/files/CSF-0.1.pdf
-/// [tip5]: crate::prelude::Tip5
-pub trait Sponge: Send + Sync {
-    const RATE: usize;
-
+/// [tip5]: crate::Tip5
+pub trait Sponge<const RATE: usize = { self::RATE }>: Send + Sync {
     fn init() -> Self;
 
-    fn absorb(&mut self, input: [BFieldElement; RATE]);
+    /// Absorb one block of input.
+    ///
+    /// Since version 0.2.0, the input is taken by reference. Callers migrating from earlier
+    /// versions pass `&input` instead of `input`.
+    fn absorb(&mut self, input: &[BFieldElement; RATE]);
 
     fn squeeze(&mut self) -> [BFieldElement; RATE];
 
+    /// Squeeze `n` elements. This is equivalent to concatenating the outputs of
+    /// ⌈n / `RATE`⌉ successive calls to [`squeeze`](Self::squeeze) and truncating the result
+    /// to `n` elements: the remaining elements of the last squeeze are discarded.
+    fn squeeze_n(&mut self, n: usize) -> Vec<BFieldElement> {
+        let mut output = vec![BFieldElement::ZERO; n];
+        self.squeeze_into(&mut output);
+        output
+    }
+
+    /// Sample `count` indices uniformly from `0..upper_bound`, for example query indices in a
+    /// Fiat-Shamir transform.
+    ///
+    /// Elements are squeezed block by block and used in order. An element is rejected if its
+    /// canonical [value](BFieldElement::value) is not smaller than the largest multiple of
+    /// `upper_bound` that is at most [`P`](BFieldElement::P); otherwise, the index is the value
+    /// modulo `upper_bound`. Since every index has the same number of preimages below that
+    /// multiple, there is no modulo bias. Elements of the last squeeze that are not needed are
+    /// discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upper_bound` is zero.
+    fn sample_indices(&mut self, upper_bound: u32, count: usize) -> Vec<u32> {
+        assert_ne!(0, upper_bound, "upper bound for indices must be positive");
+
+        let upper_bound = u64::from(upper_bound);
+        let rejection_threshold = BFieldElement::P - BFieldElement::P % upper_bound;
+        let mut indices = Vec::with_capacity(count);
+        while indices.len() < count {
+            for element in self.squeeze() {
+                let value = element.value();
+                if value < rejection_threshold && indices.len() < count {
+                    indices.push((value % upper_bound) as u32);
+                }
+            }
+        }
+        indices
+    }
+
+    /// An endless iterator over squeezed elements, yielding them one at a time. This is
+    /// equivalent to flattening successive calls to [`squeeze`](Self::squeeze), except that
+    /// the sponge is squeezed lazily: only when all elements of the previous squeeze have
+    /// been yielded. Dropping the iterator discards the remaining elements of the last
+    /// squeeze.
+    fn squeeze_elements(&mut self) -> SqueezeIter<'_, RATE, Self> {
+        SqueezeIter {
+            sponge: self,
+            block: [BFieldElement::ZERO; RATE],
+            num_yielded: RATE,
+        }
+    }
+
+    /// Squeeze `N` elements into an array, without allocating. This is equivalent to
+    /// [`squeeze_n`](Self::squeeze_n) with `N`.
+    fn squeeze_array<const N: usize>(&mut self) -> [BFieldElement; N] {
+        let mut output = [BFieldElement::ZERO; N];
+        self.squeeze_into(&mut output);
+        output
+    }
+
+    /// Fill `out` with successively squeezed elements, without allocating. This is
+    /// equivalent to [`squeeze_n`](Self::squeeze_n) with `out.len()` followed by a copy.
+    fn squeeze_into(&mut self, out: &mut [BFieldElement]) {
+        for chunk in out.chunks_mut(RATE) {
+            let block = self.squeeze();
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    /// Absorb the input, padded with [1, 0, 0, …] to a multiple of the rate. The padding is
+    /// at least one element. No memory is allocated.
     fn pad_and_absorb_all(&mut self, input: &[BFieldElement]) {
-        // pad input with [1, 0, 0, …] – padding is at least one element
+        let (last_block, _) = absorb_full_blocks(self, input);
+        self.absorb(&last_block);
+    }
+
+    /// Absorb the input, padded to a multiple of the rate according to the given [`Padding`]
+    /// scheme. No memory is allocated. With [`Padding::OneZeroStar`], this is
+    /// [`pad_and_absorb_all`](Self::pad_and_absorb_all).
+    fn pad_and_absorb_all_with(&mut self, input: &[BFieldElement], padding: Padding) {
+        if padding == Padding::OneZeroStar {
+            return self.pad_and_absorb_all(input);
+        }
+
+        let (mut last_block, remainder_len) = absorb_full_blocks(self, input);
+        if remainder_len == RATE - 1 {
+            self.absorb(&last_block);
+            last_block = [BFieldElement::ZERO; RATE];
+        }
+        last_block[RATE - 1] = BFieldElement::ONE;
+        self.absorb(&last_block);
+    }
+
+    /// Absorb a byte string, packed into field elements by [`pack_bytes`] and padded like
+    /// [`pad_and_absorb_all`](Self::pad_and_absorb_all). That is, this is equivalent to
+    /// `self.pad_and_absorb_all(&pack_bytes(bytes))`, but no memory is allocated. Since the
+    /// packing is injective, different byte strings never absorb to the same elements.
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        pad_and_absorb_elements(self, packed_bytes(bytes));
+    }
+
+    /// Absorb integers, each split into two 32-bit limbs by [`encode_u64s`], and padded like
+    /// [`pad_and_absorb_all`](Self::pad_and_absorb_all). That is, this is equivalent to
+    /// `self.pad_and_absorb_all(&encode_u64s(values))`, but no memory is allocated. Unlike
+    /// [`BFieldElement::new`], the encoding does not reduce values modulo
+    /// [`P`](BFieldElement::P), so no information is lost.
+    fn absorb_u64s(&mut self, values: &[u64]) {
+        pad_and_absorb_elements(self, encoded_u64s(values));
+    }
+
+    /// Like [`absorb_u64s`](Self::absorb_u64s), but for integers split into four 32-bit limbs
+    /// by [`encode_u128s`].
+    fn absorb_u128s(&mut self, values: &[u128]) {
+        pad_and_absorb_elements(self, encoded_u128s(values));
+    }
+
+    /// Produce a [`Digest`] from the first [`Digest::LEN`] squeezed elements, like
+    /// [`squeeze_array`](Self::squeeze_array). For [`Tip5`](crate::Tip5), these are the first
+    /// elements of the state, which is exactly what [`Tip5::hash_varlen`](crate::Tip5::hash_varlen)
+    /// returns.
+    ///
+    /// Finalizing ends the use of the sponge: the state afterwards is unspecified, and the
+    /// sponge must not be squeezed or absorbed into again. In particular, squeezing after
+    /// finalizing may repeat the digest. To derive more elements from the same state, for
+    /// example Fiat-Shamir challenges, [squeeze](Self::squeeze) instead.
+    ///
+    /// This leaves implementations free to skip the permutation that squeezing implies, which
+    /// the digest does not depend on. [`Tip5`](crate::Tip5) does so.
+    fn finalize(&mut self) -> Digest {
+        Digest::new(self.squeeze_array())
+    }
+}
+
+/// The number of bytes that [`pack_bytes`] packs into one field element.
+pub const BYTES_PER_ELEMENT: usize = 7;
+
+/// Pack a byte string into field elements. The first element is the number of bytes. Then,
+/// every chunk of [`BYTES_PER_ELEMENT`] bytes becomes one element, interpreting the chunk as a
+/// little-endian integer; the last chunk may be shorter. Since 7 bytes are always smaller than
+/// [`P`](BFieldElement::P) and the length is explicit, the packing is injective.
+///
+/// See also: [`Sponge::absorb_bytes`].
+pub fn pack_bytes(bytes: &[u8]) -> Vec<BFieldElement> {
+    packed_bytes(bytes).collect()
+}
+
+fn packed_bytes(bytes: &[u8]) -> impl Iterator<Item = BFieldElement> {
+    let length = BFieldElement::new(bytes.len() as u64);
+    let chunks = bytes.chunks(BYTES_PER_ELEMENT).map(|chunk| {
+        let mut le_bytes = [0; 8];
+        le_bytes[..chunk.len()].copy_from_slice(chunk);
+        BFieldElement::new(u64::from_le_bytes(le_bytes))
+    });
+
+    std::iter::once(length).chain(chunks)
+}
+
+/// Absorb the full blocks of the input. Returns the last block, that is, the remaining
+/// elements followed by a one and zeros, together with the number of remaining elements. The
+/// padding schemes differ only in how they finish this block.
+fn absorb_full_blocks<const RATE: usize, S: Sponge<RATE> + ?Sized>(
+    sponge: &mut S,
+    input: &[BFieldElement],
+) -> ([BFieldElement; RATE], usize) {
+    let (blocks, remainder) = input.as_chunks::<RATE>();
+    for block in blocks {
+        sponge.absorb(block);
+    }
+
+    let mut last_block = [BFieldElement::ZERO; RATE];
+    last_block[..remainder.len()].copy_from_slice(remainder);
+    last_block[remainder.len()] = BFieldElement::ONE;
+    (last_block, remainder.len())
+}
+
+/// Like [`Sponge::pad_and_absorb_all`], but for elements not already in memory.
+fn pad_and_absorb_elements<const RATE: usize, S: Sponge<RATE> + ?Sized>(
+    sponge: &mut S,
+    elements: impl Iterator<Item = BFieldElement>,
+) {
+    let mut block = [BFieldElement::ZERO; RATE];
+    let mut block_len = 0;
+    for element in elements {
+        block[block_len] = element;
+        block_len += 1;
+        if block_len == RATE {
+            sponge.absorb(&block);
+            block_len = 0;
+        }
+    }
+
+    block[block_len] = BFieldElement::ONE;
+    block[block_len + 1..].fill(BFieldElement::ZERO);
+    sponge.absorb(&block);
+}
+
+/// Encode integers injectively as field elements: every integer becomes two elements, its low
+/// and then its high 32 bits.
+///
+/// See also: [`decode_u64s`], [`Sponge::absorb_u64s`].
+pub fn encode_u64s(values: &[u64]) -> Vec<BFieldElement> {
+    encoded_u64s(values).collect()
+}
+
+/// Encode integers injectively as field elements: every integer becomes four elements, its
+/// 32-bit limbs from least to most significant.
+///
+/// See also: [`decode_u128s`], [`Sponge::absorb_u128s`].
+pub fn encode_u128s(values: &[u128]) -> Vec<BFieldElement> {
+    encoded_u128s(values).collect()
+}
+
+/// The inverse of [`encode_u64s`].
+pub fn decode_u64s(elements: &[BFieldElement]) -> Result<Vec<u64>, DecodeIntegersError> {
+    decode_limbs(elements, 2).map(|values| values.map(|value| value as u64).collect())
+}
+
+/// The inverse of [`encode_u128s`].
+pub fn decode_u128s(elements: &[BFieldElement]) -> Result<Vec<u128>, DecodeIntegersError> {
+    decode_limbs(elements, 4).map(Iterator::collect)
+}
+
+fn encoded_u64s(values: &[u64]) -> impl Iterator<Item = BFieldElement> {
+    values
+        .iter()
+        .flat_map(|&value| [value as u32, (value >> 32) as u32])
+        .map(BFieldElement::from_u32)
+}
+
+fn encoded_u128s(values: &[u128]) -> impl Iterator<Item = BFieldElement> {
+    values
+        .iter()
+        .flat_map(|&value| [0, 32, 64, 96].map(|shift| (value >> shift) as u32))
+        .map(BFieldElement::from_u32)
+}
+
+fn decode_limbs(
+    elements: &[BFieldElement],
+    limbs_per_integer: usize,
+) -> Result<impl Iterator<Item = u128>, DecodeIntegersError> {
+    if !elements.len().is_multiple_of(limbs_per_integer) {
+        return Err(DecodeIntegersError::InvalidLength(elements.len()));
+    }
+
+    let limbs = elements.iter().map(|element| element.value());
+    if let Some((limb, value)) = limbs
+        .clone()
+        .enumerate()
+        .find(|&(_, v)| v > u32::MAX.into())
+    {
+        return Err(DecodeIntegersError::LimbTooLarge { limb, value });
+    }
+
+    let integers = elements.chunks(limbs_per_integer).map(|limbs| {
+        limbs
+            .iter()
+            .rev()
+            .fold(0, |acc, limb| (acc << 32) | u128::from(limb.value()))
+    });
+    Ok(integers)
+}
+
+/// The error returned when decoding field elements into integers fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeIntegersError {
+    /// The given number of elements is not a multiple of the number of limbs per integer.
+    InvalidLength(usize),
+
+    /// The element with the given index is not a 32-bit limb.
+    LimbTooLarge { limb: usize, value: u64 },
+}
+
+impl std::fmt::Display for DecodeIntegersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(
+                    f,
+                    "{len} elements do not decode to a whole number of integers"
+                )
+            }
+            Self::LimbTooLarge { limb, value } => {
+                write!(f, "limb {limb} is {value}, must be smaller than 2^32")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeIntegersError {}
+
+/// Hash a variable-length sequence of [`BFieldElement`]s with any [`Sponge`]: initialize the
+/// sponge, [pad and absorb](Sponge::pad_and_absorb_all) the input, and
+/// [finalize](Sponge::finalize).
+pub fn hash_varlen<const RATE: usize, S: Sponge<RATE>>(input: &[BFieldElement]) -> Digest {
+    let mut sponge = S::init();
+    sponge.pad_and_absorb_all(input);
+    sponge.finalize()
+}
+
+/// An iterator over squeezed elements, created by [`Sponge::squeeze_elements`].
+pub struct SqueezeIter<'a, const RATE: usize, S: ?Sized> {
+    sponge: &'a mut S,
+    block: [BFieldElement; RATE],
+
+    /// The number of elements of `block` that have already been yielded.
+    num_yielded: usize,
+}
+
+impl<const RATE: usize, S: Sponge<RATE> + ?Sized> Iterator for SqueezeIter<'_, RATE, S> {
+    type Item = BFieldElement;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.num_yielded == RATE {
+            self.block = self.sponge.squeeze();
+            self.num_yielded = 0;
+        }
+
+        let element = self.block[self.num_yielded];
+        self.num_yielded += 1;
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<const RATE: usize, S: Sponge<RATE> + ?Sized> std::iter::FusedIterator
+    for SqueezeIter<'_, RATE, S>
+{
+}
+
+/// An object-safe companion of [`Sponge`], for holding sponges behind a `Box<dyn DynSponge>`
+/// or `&mut dyn DynSponge`. Implemented for every [`Sponge`] of the same rate.
+pub trait DynSponge<const RATE: usize = { self::RATE }>: Send + Sync {
+    /// The rate of the underlying sponge.
+    fn rate(&self) -> usize {
+        RATE
+    }
+
+    /// Absorb the input block by block, like successive calls to [`Sponge::absorb`]. No
+    /// padding is applied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the input is not a multiple of the rate.
+    fn absorb_slice(&mut self, input: &[BFieldElement]);
+
+    /// Fill `out` with successively squeezed elements, like [`Sponge::squeeze_into`].
+    fn squeeze_slice(&mut self, out: &mut [BFieldElement]);
+}
+
+impl<const RATE: usize, S: Sponge<RATE>> DynSponge<RATE> for S {
+    fn absorb_slice(&mut self, input: &[BFieldElement]) {
+        let (blocks, remainder) = input.as_chunks::<RATE>();
+        assert!(
+            remainder.is_empty(),
+            "input length must be a multiple of the rate {RATE}, got {}",
+            input.len()
+        );
+        for block in blocks {
+            self.absorb(block);
+        }
+    }
+
+    fn squeeze_slice(&mut self, out: &mut [BFieldElement]) {
+        self.squeeze_into(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::bfe_array;
+    use crate::bfe_vec;
+
+    /// The original, allocating implementation of [`Sponge::pad_and_absorb_all`].
+    fn reference_pad_and_absorb_all<const RATE: usize, S: Sponge<RATE>>(
+        sponge: &mut S,
+        input: &[BFieldElement],
+    ) {
         let padded_length = (input.len() + 1).next_multiple_of(RATE);
         let padding_iter =
             iter::once(&BFieldElement::ONE).chain(iter::repeat(&BFieldElement::ZERO));
         let padded_input = input.iter().chain(padding_iter).take(padded_length);
 
         for chunk in padded_input.chunks(RATE).into_iter() {
-            // the padded input has length some multiple of `RATE`
-            let absorb_elems = chunk.cloned().collect_vec().try_into().unwrap();
-            self.absorb(absorb_elems);
+            let absorb_elems: [_; RATE] = chunk.cloned().collect_vec().try_into().unwrap();
+            sponge.absorb(&absorb_elems);
+        }
+    }
+
+    /// A sponge with rate 4 that records the absorbed blocks instead of permuting.
+    #[derive(Debug, Default)]
+    struct RecordingSponge {
+        absorbed: Vec<[BFieldElement; 4]>,
+    }
+
+    impl Sponge<4> for RecordingSponge {
+        fn init() -> Self {
+            Self::default()
+        }
+
+        fn absorb(&mut self, input: &[BFieldElement; 4]) {
+            self.absorbed.push(*input);
+        }
+
+        fn squeeze(&mut self) -> [BFieldElement; 4] {
+            self.absorbed.last().copied().unwrap_or_default()
         }
     }
+
+    /// A sponge of any rate whose “permutation” adds a counter to the state, to check that the
+    /// provided methods work for rates other than those of [`RecordingSponge`] and Tip5.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct CounterSponge<const RATE: usize> {
+        state: [BFieldElement; RATE],
+        num_permutations: u64,
+    }
+
+    impl<const RATE: usize> CounterSponge<RATE> {
+        fn permute(&mut self) {
+            self.num_permutations += 1;
+            for (i, element) in (0..).zip(&mut self.state) {
+                *element += BFieldElement::new(100 * self.num_permutations + i);
+            }
+        }
+    }
+
+    impl<const RATE: usize> Sponge<RATE> for CounterSponge<RATE> {
+        fn init() -> Self {
+            Self {
+                state: [BFieldElement::ZERO; RATE],
+                num_permutations: 0,
+            }
+        }
+
+        fn absorb(&mut self, input: &[BFieldElement; RATE]) {
+            self.state = *input;
+            self.permute();
+        }
+
+        fn squeeze(&mut self) -> [BFieldElement; RATE] {
+            let output = self.state;
+            self.permute();
+            output
+        }
+    }
+
+    fn provided_methods_generalize_to_rate<const RATE: usize>() {
+        assert_eq!(RATE, DynSponge::rate(&CounterSponge::<RATE>::init()));
+
+        for len in 0..=3 * RATE as u64 {
+            let input = (0..len).map(BFieldElement::new).collect_vec();
+            let mut sponge = CounterSponge::<RATE>::init();
+            sponge.pad_and_absorb_all(&input);
+            let mut reference = CounterSponge::init();
+            reference_pad_and_absorb_all(&mut reference, &input);
+            assert_eq!(reference, sponge);
+            assert_eq!(len / RATE as u64 + 1, sponge.num_permutations);
+
+            let expected = sponge.clone().squeeze_n(Digest::LEN);
+            assert_eq!(expected, sponge.finalize().values());
+        }
+    }
+
+    #[test]
+    fn provided_methods_generalize_to_other_rates() {
+        provided_methods_generalize_to_rate::<1>();
+        provided_methods_generalize_to_rate::<3>();
+        provided_methods_generalize_to_rate::<7>();
+        provided_methods_generalize_to_rate::<16>();
+    }
+
+    #[test]
+    fn squeeze_elements_agrees_with_successive_squeezes() {
+        let mut sponge = CounterSponge::<3>::init();
+        sponge.pad_and_absorb_all(&bfe_array![1, 2, 3, 4]);
+        let mut reference = sponge.clone();
+
+        let elements = sponge.squeeze_elements().take(12).collect_vec();
+        let expected = (0..4).flat_map(|_| reference.squeeze()).collect_vec();
+        assert_eq!(expected, elements);
+        assert_eq!(reference, sponge);
+    }
+
+    #[test]
+    fn squeeze_elements_squeezes_lazily() {
+        for n in 0..=10 {
+            let mut sponge = CounterSponge::<4>::init();
+            let _ = sponge.squeeze_elements().take(n).collect_vec();
+            assert_eq!(n.div_ceil(4) as u64, sponge.num_permutations);
+        }
+
+        let mut sponge = CounterSponge::<4>::init();
+        {
+            let mut elements = sponge.squeeze_elements();
+            let _ = elements.next();
+            let _ = elements.nth(2);
+        }
+        assert_eq!(1, sponge.num_permutations);
+    }
+
+    #[test]
+    fn squeeze_elements_of_tip5_agrees_with_squeeze_n() {
+        let mut sponge = crate::Tip5::init();
+        sponge.pad_and_absorb_all(&bfe_array![42]);
+        let mut reference = crate::Tip5::init();
+        reference.pad_and_absorb_all(&bfe_array![42]);
+
+        let elements = sponge.squeeze_elements().take(25).collect_vec();
+        assert_eq!(reference.squeeze_n(25), elements);
+        assert_eq!(reference.state, sponge.state);
+    }
+
+    fn absorbed_blocks(input: &[BFieldElement]) -> Vec<[BFieldElement; 4]> {
+        let mut sponge = RecordingSponge::init();
+        sponge.pad_and_absorb_all(input);
+        sponge.absorbed
+    }
+
+    #[test]
+    fn rate_is_the_const_generic_parameter() {
+        assert_eq!(4, DynSponge::rate(&RecordingSponge::init()));
+        assert_eq!(10, DynSponge::rate(&<crate::Tip5 as Sponge>::init()));
+    }
+
+    #[test]
+    fn pad_and_absorb_all_respects_the_rate() {
+        assert_eq!(vec![bfe_array![1, 0, 0, 0]], absorbed_blocks(&[]));
+        assert_eq!(
+            vec![bfe_array![7, 8, 9, 1]],
+            absorbed_blocks(&bfe_array![7, 8, 9])
+        );
+        assert_eq!(
+            vec![bfe_array![1, 2, 3, 4], bfe_array![1, 0, 0, 0]],
+            absorbed_blocks(&bfe_array![1, 2, 3, 4])
+        );
+        assert_eq!(
+            vec![bfe_array![1, 2, 3, 4], bfe_array![5, 1, 0, 0]],
+            absorbed_blocks(&bfe_array![1, 2, 3, 4, 5])
+        );
+
+        let mut sponge = RecordingSponge::init();
+        sponge.pad_and_absorb_all(&bfe_array![1, 2, 3, 4, 5, 6]);
+        assert_eq!(bfe_array![5, 6, 1, 0], sponge.squeeze());
+    }
+
+    proptest! {
+        #[test]
+        fn pad_and_absorb_all_agrees_with_reference(
+            values in prop::collection::vec(0..BFieldElement::P, 0..=35),
+        ) {
+            let input = values.into_iter().map(BFieldElement::new).collect_vec();
+            let mut sponge = RecordingSponge::init();
+            sponge.pad_and_absorb_all(&input);
+            let mut reference = RecordingSponge::init();
+            reference_pad_and_absorb_all(&mut reference, &input);
+            prop_assert_eq!(reference.absorbed, sponge.absorbed);
+
+            let mut tip5 = crate::Tip5::init();
+            tip5.pad_and_absorb_all(&input);
+            let mut reference = crate::Tip5::init();
+            reference_pad_and_absorb_all(&mut reference, &input);
+            prop_assert_eq!(reference.state, tip5.state);
+        }
+    }
+
+    fn absorbed_blocks_ten_star_one(input: &[BFieldElement]) -> Vec<[BFieldElement; 4]> {
+        let mut sponge = RecordingSponge::init();
+        sponge.pad_and_absorb_all_with(input, Padding::TenStarOne);
+        sponge.absorbed
+    }
+
+    #[test]
+    fn ten_star_one_padding_respects_the_rate() {
+        assert_eq!(
+            vec![bfe_array![1, 0, 0, 1]],
+            absorbed_blocks_ten_star_one(&[])
+        );
+        assert_eq!(
+            vec![bfe_array![7, 8, 1, 1]],
+            absorbed_blocks_ten_star_one(&bfe_array![7, 8])
+        );
+        assert_eq!(
+            vec![bfe_array![7, 8, 9, 1], bfe_array![0, 0, 0, 1]],
+            absorbed_blocks_ten_star_one(&bfe_array![7, 8, 9])
+        );
+        assert_eq!(
+            vec![bfe_array![1, 2, 3, 4], bfe_array![1, 0, 0, 1]],
+            absorbed_blocks_ten_star_one(&bfe_array![1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn one_zero_star_padding_is_the_default() {
+        assert_eq!(Padding::OneZeroStar, Padding::default());
+        for len in 0..=9 {
+            let input = (0..len).map(BFieldElement::new).collect_vec();
+            let mut sponge = RecordingSponge::init();
+            sponge.pad_and_absorb_all_with(&input, Padding::OneZeroStar);
+            assert_eq!(absorbed_blocks(&input), sponge.absorbed);
+        }
+    }
+
+    fn tip5_hash_varlen_with(input: &[BFieldElement], padding: Padding) -> Digest {
+        let mut sponge = crate::Tip5::init();
+        sponge.pad_and_absorb_all_with(input, padding);
+        sponge.finalize()
+    }
+
+    #[test]
+    fn ten_star_one_padding_vectors() {
+        let expected = Digest::new_from_u64s([
+            14791300028472804648,
+            16697967278943904734,
+            9963619480524228204,
+            6553906298994226764,
+            8213733612791202514,
+        ]);
+        let digest = tip5_hash_varlen_with(&bfe_array![1, 2, 3], Padding::TenStarOne);
+        assert_eq!(expected, digest);
+
+        let mut sponge = crate::Tip5::init();
+        sponge.absorb(&bfe_array![1, 2, 3, 1, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(expected, sponge.finalize());
+
+        let expected = Digest::new_from_u64s([
+            12441388374029536503,
+            13270104643397524085,
+            9339964792933645997,
+            15933599595819154209,
+            3863529994359236624,
+        ]);
+        let input = (0..9).map(BFieldElement::new).collect_vec();
+        assert_eq!(expected, tip5_hash_varlen_with(&input, Padding::TenStarOne));
+    }
+
+    proptest! {
+        #[test]
+        fn padding_schemes_never_collide(
+            values in prop::collection::vec(0..BFieldElement::P, 0..=35),
+        ) {
+            let input = values.into_iter().map(BFieldElement::new).collect_vec();
+            prop_assert_ne!(
+                tip5_hash_varlen_with(&input, Padding::OneZeroStar),
+                tip5_hash_varlen_with(&input, Padding::TenStarOne)
+            );
+        }
+    }
+
+    #[test]
+    fn pack_bytes_vectors() {
+        assert_eq!(bfe_vec![0], pack_bytes(b""));
+        assert_eq!(bfe_vec![1, 0x61], pack_bytes(b"a"));
+        assert_eq!(bfe_vec![2, 0x61], pack_bytes(b"a\0"));
+        assert_eq!(bfe_vec![3, 0x63_62_61], pack_bytes(b"abc"));
+        assert_eq!(
+            bfe_vec![8, 0x67_66_65_64_63_62_61, 0x68],
+            pack_bytes(b"abcdefgh")
+        );
+        assert_eq!(
+            bfe_vec![7, 0x00ff_ffff_ffff_ffff],
+            pack_bytes(&[0xff; BYTES_PER_ELEMENT])
+        );
+    }
+
+    #[test]
+    fn absorb_bytes_vectors() {
+        let mut sponge = crate::Tip5::init();
+        sponge.absorb_bytes(b"Tip5");
+        let expected = Digest::new_from_u64s([
+            2183458760574342889,
+            2412459977735610512,
+            3336230368677753801,
+            2733187068256845215,
+            11808079491887944036,
+        ]);
+        assert_eq!(expected, sponge.finalize());
+        assert_eq!(expected, crate::Tip5::hash_varlen(&pack_bytes(b"Tip5")));
+
+        let mut sponge = crate::Tip5::init();
+        sponge.absorb_bytes(&[0; 100]);
+        let expected = Digest::new_from_u64s([
+            15521770561524516357,
+            11474949253198622028,
+            8739115663238767731,
+            10830159066453898305,
+            7329858663040766738,
+        ]);
+        assert_eq!(expected, sponge.finalize());
+    }
+
+    proptest! {
+        #[test]
+        fn absorb_bytes_agrees_with_absorbing_packed_bytes(
+            bytes in prop::collection::vec(any::<u8>(), 0..=100),
+        ) {
+            let mut sponge = RecordingSponge::init();
+            sponge.absorb_bytes(&bytes);
+            prop_assert_eq!(absorbed_blocks(&pack_bytes(&bytes)), sponge.absorbed);
+        }
+
+        #[test]
+        fn pack_bytes_is_injective(
+            bytes in prop::collection::vec(any::<u8>(), 0..=50),
+            other in prop::collection::vec(any::<u8>(), 0..=50),
+        ) {
+            prop_assume!(bytes != other);
+            prop_assert_ne!(pack_bytes(&bytes), pack_bytes(&other));
+        }
+
+        #[test]
+        fn appending_zero_bytes_changes_the_packing(
+            bytes in prop::collection::vec(any::<u8>(), 0..=50),
+            num_zeros in 1_usize..10,
+        ) {
+            let mut extended = bytes.clone();
+            extended.extend(std::iter::repeat_n(0, num_zeros));
+            prop_assert_ne!(pack_bytes(&bytes), pack_bytes(&extended));
+        }
+    }
+
+    #[test]
+    fn integer_encoding_vectors() {
+        assert_eq!(bfe_vec![0, 0], encode_u64s(&[0]));
+        assert_eq!(
+            bfe_vec![0x89ab_cdef, 0x0123_4567],
+            encode_u64s(&[0x0123_4567_89ab_cdef])
+        );
+        assert_eq!(
+            bfe_vec![1, 0, 0, 0, 0, 0, 0, 1],
+            encode_u128s(&[1, 1 << 96])
+        );
+        assert_eq!(
+            vec![BFieldElement::new(u32::MAX.into()); 4],
+            encode_u128s(&[u128::MAX])
+        );
+    }
+
+    #[test]
+    fn u64s_straddling_p_hash_differently_from_their_reductions() {
+        let hash = |values: &[u64]| {
+            let mut sponge = crate::Tip5::init();
+            sponge.absorb_u64s(values);
+            sponge.finalize()
+        };
+
+        let p = BFieldElement::P;
+        for (value, reduced) in [(p, 0), (p + 1, 1), (u64::MAX, u64::MAX - p)] {
+            assert_eq!(BFieldElement::new(value), BFieldElement::new(reduced));
+            assert_ne!(hash(&[value]), hash(&[reduced]));
+        }
+        assert_ne!(hash(&[p - 1]), hash(&[p]));
+    }
+
+    #[test]
+    fn absorbing_integers_agrees_with_absorbing_their_encoding() {
+        let values = [0, 1, u64::MAX, BFieldElement::P, 1 << 32];
+        let mut sponge = RecordingSponge::init();
+        sponge.absorb_u64s(&values);
+        assert_eq!(absorbed_blocks(&encode_u64s(&values)), sponge.absorbed);
+
+        let values = [0, u128::MAX, u128::from(BFieldElement::P) << 64];
+        let mut sponge = RecordingSponge::init();
+        sponge.absorb_u128s(&values);
+        assert_eq!(absorbed_blocks(&encode_u128s(&values)), sponge.absorbed);
+    }
+
+    #[test]
+    fn decoding_invalid_limbs_fails() {
+        assert_eq!(
+            Err(DecodeIntegersError::InvalidLength(3)),
+            decode_u64s(&bfe_array![1, 2, 3])
+        );
+        assert_eq!(
+            Err(DecodeIntegersError::InvalidLength(6)),
+            decode_u128s(&bfe_array![1, 2, 3, 4, 5, 6])
+        );
+
+        let err = DecodeIntegersError::LimbTooLarge {
+            limb: 1,
+            value: 1 << 32,
+        };
+        assert_eq!(Err(err), decode_u64s(&bfe_array![0, 1 << 32]));
+        assert_eq!(Err(err), decode_u128s(&bfe_array![0, 1 << 32, 0, 0]));
+    }
+
+    proptest! {
+        #[test]
+        fn u64_encoding_round_trips(values: Vec<u64>) {
+            prop_assert_eq!(values.clone(), decode_u64s(&encode_u64s(&values)).unwrap());
+        }
+
+        #[test]
+        fn u128_encoding_round_trips(values: Vec<u128>) {
+            prop_assert_eq!(values.clone(), decode_u128s(&encode_u128s(&values)).unwrap());
+        }
+    }
+
+    #[test]
+    fn squeeze_n_agrees_with_successive_squeezes() {
+        for n in [0, 1, 10, 11, 25] {
+            let mut sponge = crate::Tip5::init();
+            sponge.pad_and_absorb_all(&bfe_array![1, 2, 3]);
+            let mut manual = crate::Tip5::init();
+            manual.pad_and_absorb_all(&bfe_array![1, 2, 3]);
+
+            let output = sponge.squeeze_n(n);
+            let mut expected = vec![];
+            for _ in 0..n.div_ceil(crate::RATE) {
+                expected.extend(manual.squeeze());
+            }
+            expected.truncate(n);
+
+            assert_eq!(expected, output, "n = {n}");
+            assert_eq!(manual.state, sponge.state, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn squeeze_into_agrees_with_squeeze_n() {
+        for n in [0, 3, 10, 17, 20, 35] {
+            let mut sponge = crate::Tip5::init();
+            sponge.pad_and_absorb_all(&bfe_array![4, 5, 6]);
+            let mut allocating = crate::Tip5::init();
+            allocating.pad_and_absorb_all(&bfe_array![4, 5, 6]);
+
+            let mut output = vec![BFieldElement::ONE; n];
+            sponge.squeeze_into(&mut output);
+            assert_eq!(allocating.squeeze_n(n), output, "n = {n}");
+            assert_eq!(allocating.state, sponge.state, "n = {n}");
+        }
+    }
+
+    fn squeeze_array_agrees_with_squeeze_n<const N: usize>() {
+        let mut sponge = crate::Tip5::init();
+        sponge.pad_and_absorb_all(&bfe_array![7, 8, 9]);
+        let mut allocating = crate::Tip5::init();
+        allocating.pad_and_absorb_all(&bfe_array![7, 8, 9]);
+
+        let output = sponge.squeeze_array::<N>();
+        assert_eq!(allocating.squeeze_n(N), output, "N = {N}");
+        assert_eq!(allocating.state, sponge.state, "N = {N}");
+    }
+
+    #[test]
+    fn squeeze_array_of_various_lengths() {
+        squeeze_array_agrees_with_squeeze_n::<0>();
+        squeeze_array_agrees_with_squeeze_n::<4>();
+        squeeze_array_agrees_with_squeeze_n::<10>();
+        squeeze_array_agrees_with_squeeze_n::<16>();
+        squeeze_array_agrees_with_squeeze_n::<30>();
+    }
+
+    #[test]
+    fn dyn_sponge_agrees_with_static_sponge() {
+        let input = bfe_array![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let mut sponge: Box<dyn DynSponge> = Box::new(crate::Tip5::init());
+
+        let mut padded_input = input.to_vec();
+        padded_input.push(BFieldElement::ONE);
+        padded_input.resize(
+            padded_input.len().next_multiple_of(sponge.rate()),
+            BFieldElement::ZERO,
+        );
+        sponge.absorb_slice(&padded_input);
+
+        let mut digest = [BFieldElement::ZERO; crate::Digest::LEN];
+        sponge.squeeze_slice(&mut digest);
+        assert_eq!(crate::Tip5::hash_varlen(&input).values(), digest);
+    }
+
+    #[test]
+    fn dyn_sponges_of_other_rates() {
+        let mut sponge: Box<dyn DynSponge<4>> = Box::new(RecordingSponge::init());
+        assert_eq!(4, sponge.rate());
+        sponge.absorb_slice(&bfe_array![1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let mut output = [BFieldElement::ZERO; 2];
+        sponge.squeeze_slice(&mut output);
+        assert_eq!(bfe_array![5, 6], output);
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of the rate 10, got 3")]
+    fn dyn_sponge_rejects_partial_blocks() {
+        let mut sponge: Box<dyn DynSponge> = Box::new(crate::Tip5::init());
+        sponge.absorb_slice(&bfe_array![1, 2, 3]);
+    }
+
+    #[test]
+    fn sample_indices_is_deterministic() {
+        let mut sponge = crate::Tip5::init();
+        sponge.pad_and_absorb_all(&bfe_array![42]);
+        let mut other = crate::Tip5::init();
+        other.pad_and_absorb_all(&bfe_array![42]);
+
+        let indices = sponge.sample_indices(1 << 20, 40);
+        assert_eq!(40, indices.len());
+        assert!(indices.iter().all(|&index| index < 1 << 20));
+        assert_eq!(indices, other.sample_indices(1 << 20, 40));
+        assert_eq!(other.state, sponge.state);
+    }
+
+    #[test]
+    fn sample_indices_rejects_values_causing_modulo_bias() {
+        // P ≡ 1 (mod 3), so P - 1 is the only value to be rejected for upper bound 3.
+        let mut sponge = RecordingSponge::init();
+        sponge.absorb(&bfe_array![-1, -2, 5, 7]);
+        assert_eq!(vec![2, 2, 1], sponge.sample_indices(3, 3));
+        assert_eq!(vec![2, 2, 1, 2, 2, 1], sponge.sample_indices(3, 6));
+
+        // Similarly, P ≡ 1 (mod 2^32 - 1).
+        let max = u32::MAX - 1;
+        assert_eq!(vec![max, 5, 7, max], sponge.sample_indices(u32::MAX, 4));
+    }
+
+    #[test]
+    fn sample_indices_handles_non_power_of_two_bounds() {
+        let mut sponge = crate::Tip5::init();
+        for upper_bound in [1, 3, 7, 100, 1_000_003, u32::MAX] {
+            let indices = sponge.sample_indices(upper_bound, 25);
+            assert_eq!(25, indices.len());
+            assert!(indices.iter().all(|&index| index < upper_bound));
+        }
+    }
+
+    #[test]
+    fn sampled_small_indices_are_uniform() {
+        const UPPER_BOUND: u32 = 6;
+        const NUM_SAMPLES: usize = 60_000;
+
+        let mut sponge = crate::Tip5::init();
+        let mut counts = [0_usize; UPPER_BOUND as usize];
+        for index in sponge.sample_indices(UPPER_BOUND, NUM_SAMPLES) {
+            counts[index as usize] += 1;
+        }
+
+        // chi-squared with 5 degrees of freedom; 20.5 is the 0.999 quantile
+        let expected = NUM_SAMPLES as f64 / f64::from(UPPER_BOUND);
+        let chi_squared = counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        assert!(
+            chi_squared < 20.5,
+            "chi-squared {chi_squared} for {counts:?}"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "upper bound for indices must be positive")]
+    fn sample_indices_rejects_zero_upper_bound() {
+        crate::Tip5::init().sample_indices(0, 1);
+    }
+
+    #[test]
+    fn generic_hash_varlen_works_for_toy_sponge() {
+        let digest = hash_varlen::<4, RecordingSponge>(&bfe_array![7, 8, 9]);
+        assert_eq!(Digest::new(bfe_array![7, 8, 9, 1, 7]), digest);
+    }
+
+    #[test]
+    fn generic_hash_varlen_agrees_with_tip5_hash_varlen() {
+        for len in 0..=25 {
+            let input = (0..len).map(BFieldElement::new).collect::<Vec<_>>();
+            let digest = hash_varlen::<_, crate::Tip5>(&input);
+            assert_eq!(crate::Tip5::hash_varlen(&input), digest);
+        }
+    }
+
+    #[test]
+    fn hash_varlen_permutes_once_per_padded_block() {
+        fn num_permutations<const RATE: usize, S: Sponge<RATE>>(len: usize) -> usize {
+            let input = vec![BFieldElement::ZERO; len];
+            crate::num_permutations_of(|| {
+                hash_varlen::<RATE, S>(&input);
+            })
+        }
+
+        for len in 0..=30 {
+            assert_eq!(len / 10 + 1, num_permutations::<10, crate::Tip5>(len));
+            assert_eq!(len / 8 + 1, num_permutations::<8, crate::Tip5Rate8>(len));
+        }
+    }
+
+    #[test]
+    fn finalize_reads_first_elements_of_tip5_state() {
+        let mut sponge = crate::Tip5::init();
+        sponge.pad_and_absorb_all(&bfe_array![1, 2, 3]);
+        let expected = Digest::from_slice_unchecked(&sponge.state[..Digest::LEN]);
+        assert_eq!(expected, sponge.finalize());
+    }
 }
@@ -0,0 +1,180 @@
+use crate::AbsorbMode;
+use crate::Domain;
+use crate::STATE_SIZE;
+use crate::Sponge;
+use crate::Tip5;
+use crate::b_field_element::BFieldElement;
+use crate::digest::Digest;
+
+const RATE: usize = 8;
+
+/// A sponge based on the [Tip5] permutation with rate 8 and capacity 8, instead of rate 10 and
+/// capacity 6.
+///
+/// The larger capacity raises the generic security bound of the sponge construction from
+/// 6 · 64 / 2 = 192 to 8 · 64 / 2 = 256 bits, at the cost of absorbing 20% fewer elements per
+/// permutation. Note that the permutation itself targets 160 bits of security; the larger
+/// capacity is a margin against generic attacks, not a stronger permutation.
+///
+/// Digests of [`Tip5Rate8`] and [`Tip5`] are unrelated, even for the same input.
+pub struct Tip5Rate8 {
+    /// The permutation is shared with [`Tip5`]; its rate part is the first 8 elements.
+    tip5: Tip5,
+}
+
+impl Tip5Rate8 {
+    /// Like [`Tip5::new`], but the [`Domain`] determines the initial value of the 8 capacity
+    /// elements.
+    ///
+    /// Additionally, the last capacity element is increased by the rate, 8. For [`Tip5`], that
+    /// element is initialized to 0, 1, or 2, depending on the domain, and neither sponge ever
+    /// overwrites it. Hence, the two sponges never permute the same state first, even if
+    /// the input, padded to the respective rate, agrees.
+    #[inline]
+    pub const fn new(domain: Domain) -> Self {
+        let mut state = crate::initial_state(domain, RATE);
+        let last = state[STATE_SIZE - 1].value();
+        state[STATE_SIZE - 1] = BFieldElement::new(last + RATE as u64);
+
+        let tip5 = Tip5 {
+            state,
+            absorb_mode: AbsorbMode::Overwrite,
+        };
+        Self { tip5 }
+    }
+
+    /// The sponge state. The first 8 elements are the rate, the rest is the capacity.
+    pub const fn state(&self) -> &[BFieldElement; STATE_SIZE] {
+        &self.tip5.state
+    }
+
+    /// Hash 8 [`BFieldElement`]s, the analogue of [`Tip5::hash_10`].
+    ///
+    /// There is no input-padding because the input length is fixed. Two [`Digest`]s do not
+    /// fit into the rate, so there is no analogue of [`Tip5::hash_pair`].
+    pub fn hash_8(input: &[BFieldElement; RATE]) -> [BFieldElement; Digest::LEN] {
+        let mut sponge = Self::new(Domain::FixedLength);
+        sponge.tip5.state[..RATE].copy_from_slice(input);
+        sponge.tip5.permute();
+        *sponge.tip5.state.first_chunk().unwrap()
+    }
+
+    /// Hash a variable-length sequence of [`BFieldElement`]s, the analogue of
+    /// [`Tip5::hash_varlen`].
+    pub fn hash_varlen(input: &[BFieldElement]) -> Digest {
+        crate::hash_varlen::<RATE, Self>(input)
+    }
+}
+
+impl Sponge<RATE> for Tip5Rate8 {
+    fn init() -> Self {
+        Self::new(Domain::VariableLength)
+    }
+
+    fn absorb(&mut self, input: &[BFieldElement; RATE]) {
+        self.tip5.state[..RATE].copy_from_slice(input);
+        self.tip5.permute();
+    }
+
+    fn squeeze(&mut self) -> [BFieldElement; RATE] {
+        let produce = *self.tip5.state.first_chunk().unwrap();
+        self.tip5.permute();
+        produce
+    }
+
+    /// Like [`Tip5`]'s `finalize`, this does not permute the state.
+    fn finalize(&mut self) -> Digest {
+        Digest::from_slice_unchecked(&self.tip5.state[..Digest::LEN])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use num_traits::ConstZero;
+
+    use super::*;
+    use crate::bfe_array;
+
+    #[test]
+    fn rate_and_capacity() {
+        assert_eq!(8, crate::DynSponge::rate(&Tip5Rate8::init()));
+        assert_eq!(8, STATE_SIZE - RATE);
+    }
+
+    #[test]
+    fn domains_initialize_the_capacity() {
+        let variable_length = Tip5Rate8::init();
+        let mut expected = [BFieldElement::ZERO; STATE_SIZE];
+        expected[STATE_SIZE - 1] = BFieldElement::new(8);
+        assert_eq!(&expected, variable_length.state());
+
+        let fixed_length = Tip5Rate8::new(Domain::FixedLength);
+        assert_eq!(&[BFieldElement::ZERO; RATE], &fixed_length.state()[..RATE]);
+        let expected = bfe_array![1, 1, 1, 1, 1, 1, 1, 9];
+        assert_eq!(&expected, &fixed_length.state()[RATE..]);
+
+        let custom = Tip5Rate8::new(Domain::Custom(0x0123_4567_89ab_cdef));
+        let expected = bfe_array![0x89ab_cdef, 0x0123_4567, 2, 2, 2, 2, 2, 10];
+        assert_eq!(&expected, &custom.state()[RATE..]);
+    }
+
+    #[test]
+    fn hash_varlen_known_answers() {
+        let expected = Digest::new_from_u64s([
+            11097824873177919616,
+            2846853611576688677,
+            6366348018179726132,
+            7803701624888647198,
+            14679518403126117467,
+        ]);
+        assert_eq!(expected, Tip5Rate8::hash_varlen(&[]));
+
+        let input = (0..20).map(BFieldElement::new).collect_vec();
+        let expected = Digest::new_from_u64s([
+            16507786952228054674,
+            4458883121485276071,
+            4106742805561441562,
+            3963077543087524744,
+            3928675011347497584,
+        ]);
+        assert_eq!(expected, Tip5Rate8::hash_varlen(&input));
+    }
+
+    #[test]
+    fn hash_8_known_answer() {
+        let expected = [
+            16927484783615578516,
+            12877049054046772119,
+            140835899792879573,
+            2026006432643664577,
+            972503735464623645,
+        ]
+        .map(BFieldElement::new);
+        assert_eq!(
+            expected,
+            Tip5Rate8::hash_8(&bfe_array![1, 2, 3, 4, 5, 6, 7, 8])
+        );
+    }
+
+    #[test]
+    fn digests_differ_from_rate_10_digests() {
+        for len in 0..=25 {
+            let input = (0..len).map(BFieldElement::new).collect_vec();
+            assert_ne!(Tip5::hash_varlen(&input), Tip5Rate8::hash_varlen(&input));
+        }
+
+        let input = bfe_array![1, 2, 3, 4, 5, 6, 7, 8];
+        let padded = bfe_array![1, 2, 3, 4, 5, 6, 7, 8, 1, 1];
+        assert_ne!(Tip5::hash_10(&padded), Tip5Rate8::hash_8(&input));
+    }
+
+    #[test]
+    fn generic_sponge_methods_work_for_rate_8() {
+        let input = (0..20).map(BFieldElement::new).collect_vec();
+        let mut sponge = Tip5Rate8::init();
+        sponge.pad_and_absorb_all(&input);
+        assert_eq!(8, Tip5Rate8::init().squeeze_n(8).len());
+        assert_eq!(Tip5Rate8::hash_varlen(&input), sponge.finalize());
+    }
+}
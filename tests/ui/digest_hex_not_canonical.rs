@@ -0,0 +1,5 @@
+use tip5_hash::digest;
+
+fn main() {
+    let _ = digest!("0100000000000000020000000000000001000000ffffffff0400000000000000ffffffffffffffff");
+}
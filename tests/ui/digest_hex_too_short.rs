@@ -0,0 +1,5 @@
+use tip5_hash::digest;
+
+fn main() {
+    let _ = digest!("850ca3c5e6ee04189bd2bc1fd9342425b097463e0c4d9773543941dc4e9989e8cac66b6cb80e3b3");
+}
@@ -0,0 +1,5 @@
+use tip5_hash::digest;
+
+fn main() {
+    let _ = digest!(1, 2, 0xffff_ffff_0000_0001, 4, 5);
+}
@@ -0,0 +1,5 @@
+use tip5_hash::digest;
+
+fn main() {
+    let _ = digest!(1, 2, 3, 4);
+}